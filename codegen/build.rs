@@ -6,10 +6,10 @@ fn main() -> Result<(), String> {
         use std::process::Command;
 
         #[cfg(unix)]
-        let command = Command::new("touch").args(&["build.rs"]).output();
+        let command = Command::new("touch").args(["build.rs"]).output();
 
         #[cfg(windows)]
-        let command = Command::new("cmd").args(&["/k", "\"copy /b build.rs +,\""]).output();
+        let command = Command::new("cmd").args(["/k", "\"copy /b build.rs +,\""]).output();
 
         // Check if successful
         //
@@ -0,0 +1,243 @@
+use crate::utils::error_mapping;
+use include_crypt_crypto::compression::Algorithm;
+use std::path::Path;
+use syn::parse::{Parse, ParseStream};
+
+/// The compression algorithm and level of the `compress` option.
+///
+/// # Example
+///
+/// The level is optional and defaults to the default level of the algorithm:
+/// ```text
+/// compress = zstd
+/// compress = brotli(9)
+/// compress = none
+/// ```
+#[derive(Clone, Copy)]
+pub(crate) struct Compression {
+    algorithm: Algorithm,
+    #[cfg_attr(
+        not(any(feature = "deflate", feature = "zstd", feature = "brotli")),
+        allow(dead_code)
+    )]
+    level: i32,
+}
+
+/// The maximum size of the zstd dictionary of a folder. The dictionary is at
+/// most a tenth of the size of the samples it's trained on.
+#[cfg(feature = "zstd")]
+const DICTIONARY_SIZE: usize = 110 * 1024;
+
+/// Large files are split into samples of this size, so that folders with a
+/// few large files can be trained on as well.
+#[cfg(feature = "zstd")]
+const SAMPLE_SIZE: usize = 16 * 1024;
+
+/// The extensions of formats which are already compressed, e.g. images,
+/// archives, fonts and media files. Compressing them again only wastes build
+/// time.
+const COMPRESSED_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "avif", "heic", "zip", "gz", "tgz", "bz2", "xz", "zst", "lz4", "br", "7z",
+    "rar", "jar", "woff", "woff2", "mp3", "mp4", "m4a", "aac", "ogg", "opus", "webm", "mkv", "mov", "flac",
+];
+
+impl Compression {
+    /// The file is stored as it is.
+    pub(crate) const NONE: Self = Self {
+        algorithm: Algorithm::None,
+        level: 0,
+    };
+
+    /// Returns the compression algorithm.
+    pub(crate) fn algorithm(&self) -> Algorithm { self.algorithm }
+
+    /// Returns the compression for the file. Files in formats which are
+    /// already compressed are stored as they are.
+    pub(crate) fn for_file(self, file_path: &str) -> Self {
+        let extension = Path::new(file_path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+
+        match extension {
+            Some(extension) if COMPRESSED_EXTENSIONS.contains(&extension.as_str()) => Self::NONE,
+            _ => self,
+        }
+    }
+
+    /// Trains the zstd dictionary of `compress = zstd_dict` on the content of
+    /// the files which are compressed. If the dictionary can't be trained,
+    /// e.g. because there are too few files, the files are compressed with
+    /// zstd without a dictionary.
+    #[cfg_attr(not(feature = "zstd"), allow(unused_variables))]
+    pub(crate) fn train(self, file_paths: &[String], contents: &[Vec<u8>]) -> (Self, Option<Vec<u8>>) {
+        #[cfg(feature = "zstd")]
+        if self.algorithm == Algorithm::ZstdDictionary {
+            let samples = file_paths
+                .iter()
+                .zip(contents)
+                .filter(|(file_path, _)| self.for_file(file_path).algorithm != Algorithm::None)
+                .flat_map(|(_, content)| content.chunks(SAMPLE_SIZE))
+                .collect::<Vec<_>>();
+            let size = DICTIONARY_SIZE.min(samples.iter().map(|sample| sample.len()).sum::<usize>() / 10);
+
+            return match zstd::dict::from_samples(&samples, size) {
+                Ok(dictionary) => (self, Some(dictionary)),
+                Err(_) => (
+                    Self {
+                        algorithm: Algorithm::Zstd,
+                        ..self
+                    },
+                    None,
+                ),
+            };
+        }
+
+        (self, None)
+    }
+
+    /// Compresses the content of the file and stores the id of the algorithm
+    /// behind it. If the compressed content isn't smaller, the file is
+    /// stored as it is, so that it doesn't have to be decompressed at runtime.
+    pub(crate) fn compress(&self, data: Vec<u8>) -> syn::Result<Vec<u8>> { self.compress_with(data, None) }
+
+    /// Compresses the content of the file like [`Compression::compress`] with
+    /// the dictionary of the folder.
+    pub(crate) fn compress_with(&self, data: Vec<u8>, dictionary: Option<&[u8]>) -> syn::Result<Vec<u8>> {
+        let compressed = match self.algorithm {
+            Algorithm::None => None,
+            _ => Some(self.encode(&data, dictionary)?),
+        };
+
+        Ok(match compressed {
+            Some(compressed) if compressed.len() < data.len() => [&compressed[..], &[self.algorithm.id()]].concat(),
+            _ => [&data[..], &[Algorithm::None.id()]].concat(),
+        })
+    }
+
+    /// Compresses the data with the algorithm. The dictionary is only used by
+    /// zstd.
+    #[cfg_attr(not(feature = "zstd"), allow(unused_variables))]
+    fn encode(&self, data: &[u8], dictionary: Option<&[u8]>) -> syn::Result<Vec<u8>> {
+        match self.algorithm {
+            #[cfg(feature = "deflate")]
+            Algorithm::Deflate => Ok(miniz_oxide::deflate::compress_to_vec(data, self.level as u8)),
+            #[cfg(feature = "zstd")]
+            Algorithm::Zstd => zstd::encode_all(data, self.level).map_err(error_mapping),
+            #[cfg(feature = "zstd")]
+            Algorithm::ZstdDictionary => {
+                let dictionary =
+                    dictionary.ok_or_else(|| error_mapping("`zstd_dict` can only be used by `include_dir!`"))?;
+
+                zstd::bulk::Compressor::with_dictionary(self.level, dictionary)
+                    .and_then(|mut compressor| compressor.compress(data))
+                    .map_err(error_mapping)
+            }
+            #[cfg(feature = "lz4")]
+            Algorithm::Lz4 => {
+                use std::io::Write;
+
+                let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+                encoder.write_all(data).map_err(error_mapping)?;
+                encoder.finish().map_err(error_mapping)
+            }
+            #[cfg(feature = "brotli")]
+            Algorithm::Brotli => {
+                use std::io::Write;
+
+                let mut encoder = brotli::CompressorWriter::new(Vec::new(), 4096, self.level as u32, 22);
+                encoder.write_all(data).map_err(error_mapping)?;
+                Ok(encoder.into_inner())
+            }
+            #[allow(unreachable_patterns)]
+            algorithm => Err(error_mapping(format!("The `{}` feature is not enabled", algorithm))),
+        }
+    }
+
+    /// Returns the feature of the algorithm if its encoder hasn't been
+    /// compiled in.
+    fn missing_feature(algorithm: Algorithm) -> Option<&'static str> {
+        let (feature, enabled) = match algorithm {
+            Algorithm::None => return None,
+            Algorithm::Deflate => ("deflate", cfg!(feature = "deflate")),
+            Algorithm::Zstd | Algorithm::ZstdDictionary => ("zstd", cfg!(feature = "zstd")),
+            Algorithm::Lz4 => ("lz4", cfg!(feature = "lz4")),
+            Algorithm::Brotli => ("brotli", cfg!(feature = "brotli")),
+        };
+
+        if enabled {
+            None
+        } else {
+            Some(feature)
+        }
+    }
+}
+
+/// Files are compressed with deflate if the `compression` feature is set and
+/// stored as they are otherwise. The algorithm features on their own only make
+/// the algorithms available to the `compress` option.
+impl Default for Compression {
+    fn default() -> Self {
+        let algorithm = if cfg!(feature = "compression") {
+            Algorithm::Deflate
+        } else {
+            Algorithm::None
+        };
+        let level = algorithm.levels().map(|(_, level)| level).unwrap_or_default();
+
+        Self { algorithm, level }
+    }
+}
+
+impl Parse for Compression {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let ident = input.parse::<syn::Ident>()?;
+        let algorithm = ident.to_string().parse::<Algorithm>().map_err(|_| {
+            syn::Error::new(
+                ident.span(),
+                "Expected `zstd`, `zstd_dict`, `lz4`, `brotli`, `deflate` or `none`",
+            )
+        })?;
+        if let Some(feature) = Self::missing_feature(algorithm) {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!(
+                    "The `{}` feature of include-crypt has to be enabled to compress with {}",
+                    feature, algorithm
+                ),
+            ));
+        }
+
+        // The level is optional and has to be in the range of the algorithm.
+        //
+        let level = if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            let level = content.parse::<syn::LitInt>()?;
+
+            match algorithm.levels() {
+                Some((levels, _)) if levels.contains(&level.base10_parse()?) => level.base10_parse()?,
+                Some((levels, _)) => {
+                    return Err(syn::Error::new(
+                        level.span(),
+                        format!(
+                            "The level of {} has to be between {} and {}",
+                            algorithm,
+                            levels.start(),
+                            levels.end()
+                        ),
+                    ))
+                }
+                None => {
+                    return Err(syn::Error::new(
+                        level.span(),
+                        format!("{} doesn't support levels", algorithm),
+                    ))
+                }
+            }
+        } else {
+            algorithm.levels().map(|(_, level)| level).unwrap_or_default()
+        };
+
+        Ok(Self { algorithm, level })
+    }
+}
@@ -0,0 +1,189 @@
+use crate::utils::{error_mapping, relative_path, KeyRng};
+use sha2::{Digest, Sha256};
+use std::{fs::OpenOptions, io::Write, path::PathBuf, rc::Rc};
+
+/// The environment variable which enables the key escrow. It contains either
+/// the path of the escrow file, or `1` to use the default path.
+pub(crate) const ESCROW_VARIABLE: &str = "INCLUDE_CRYPT_ESCROW";
+
+/// The name of the escrow file in `OUT_DIR` or `CARGO_TARGET_DIR`.
+const ESCROW_FILE_NAME: &str = "include-crypt-escrow.jsonl";
+
+/// The length of the id of a macro invocation in bytes.
+const ESCROW_ID_LEN: usize = 16;
+
+/// The file whose key material is written to the escrow file.
+struct EscrowFile {
+    /// The path of the escrow file.
+    escrow_path: PathBuf,
+
+    /// The name of the crate that embeds the file.
+    crate_name: String,
+
+    /// The path of the file as specified in the macro.
+    file_path: String,
+
+    /// The hex encoded SHA-256 of the content of the file.
+    hash: String,
+
+    /// The hex encoded id of the macro invocation. It's derived from the seed
+    /// and the call site for reproducible builds and random otherwise.
+    id: String,
+}
+
+/// Writes the key material of a file to the escrow file, so that the embedded
+/// buffer can be decrypted outside of the program.
+///
+/// The escrow is only enabled if the `INCLUDE_CRYPT_ESCROW` environment
+/// variable is set. Every key is appended as a JSON object on its own line and
+/// identified by the crate, the path of the file, the hash of its content, the
+/// id of the macro invocation and the name of the key. The file is only ever
+/// appended to, so a reader keeps the last line of every identity. Only
+/// generated keys are written, keys that have been specified, looked up in a
+/// keyring or derived from a password are skipped. The escrow file is never
+/// referenced by the generated code.
+#[derive(Clone)]
+pub(crate) struct Escrow {
+    file: Option<Rc<EscrowFile>>,
+
+    /// The prefix of the key names, e.g. for the layers of a cascade.
+    prefix: String,
+}
+
+impl Escrow {
+    /// Creates the escrow for the specified file.
+    ///
+    /// # Parameters
+    ///
+    /// - `file_path`: The path of the file as specified in the macro, or the
+    ///   path of the folder for the solid archive of a folder.
+    /// - `content`: The uncompressed content, which identifies the file
+    ///   together with its path. It's only hashed if the escrow is enabled.
+    pub(crate) fn new(file_path: &str, content: &[u8]) -> syn::Result<Self> {
+        let escrow_path = match std::env::var(ESCROW_VARIABLE) {
+            Ok(path) if path == "1" => default_path()?,
+            Ok(path) if !path.is_empty() => PathBuf::from(path),
+            _ => return Ok(Self::disabled()),
+        };
+
+        // The same file can be embedded by several macros, which would otherwise
+        // share the identity of their keys.
+        //
+        let id = KeyRng::new(file_path, content).derive("escrow", ESCROW_ID_LEN);
+
+        let file = EscrowFile {
+            escrow_path,
+            crate_name: std::env::var("CARGO_PKG_NAME").unwrap_or_default(),
+            file_path: relative_path(file_path),
+            hash: hex::encode(Sha256::digest(content)),
+            id: hex::encode(&*id),
+        };
+
+        Ok(Self {
+            file: Some(Rc::new(file)),
+            prefix: String::new(),
+        })
+    }
+
+    /// Returns an escrow that doesn't write anything.
+    pub(crate) fn disabled() -> Self {
+        Self {
+            file: None,
+            prefix: String::new(),
+        }
+    }
+
+    /// Returns the escrow for the specified layer of a cascade.
+    pub(crate) fn layer(&self, layer: usize) -> Self { self.prefixed(&format!("layer_{}.", layer)) }
+
+    /// Returns the escrow whose key names start with the prefix, e.g.
+    /// `dictionary/` for the dictionary of a folder.
+    pub(crate) fn prefixed(&self, prefix: &str) -> Self {
+        Self {
+            file: self.file.clone(),
+            prefix: format!("{}{}", self.prefix, prefix),
+        }
+    }
+
+    /// Appends the key to the escrow file. Empty keys, e.g. the nonce of
+    /// ciphers without nonce, are skipped. The macros are expanded again on
+    /// every build, so the file may contain earlier lines of the same key. A
+    /// line that is already in the file isn't appended again.
+    ///
+    /// # Parameters
+    ///
+    /// - `name`: The name of the key, e.g. `key`, `nonce` or `mac_key`.
+    /// - `key`: The key material.
+    pub(crate) fn record(&self, name: &str, key: &[u8]) -> syn::Result<()> {
+        let file = match &self.file {
+            Some(file) if !key.is_empty() => file,
+            _ => return Ok(()),
+        };
+
+        let line = format!(
+            "{{\"crate\":{},\"path\":{},\"hash\":\"{}\",\"id\":\"{}\",\"name\":{},\"key\":\"{}\"}}\n",
+            json_string(&file.crate_name),
+            json_string(&file.file_path),
+            file.hash,
+            file.id,
+            json_string(&format!("{}{}", self.prefix, name)),
+            hex::encode(key),
+        );
+
+        let map_error =
+            |e: std::io::Error| error_mapping(format!("Failed to write the escrow file {:?}: {}", file.escrow_path, e));
+        if let Some(parent) = file.escrow_path.parent() {
+            std::fs::create_dir_all(parent).map_err(map_error)?;
+        }
+
+        // The file is never rewritten, because the macros of other crates might
+        // append to it at the same time. A line that is appended twice by a race
+        // is harmless.
+        //
+        match std::fs::read_to_string(&file.escrow_path) {
+            Ok(existing) if existing.lines().any(|other| other == line.trim_end()) => return Ok(()),
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(map_error(e)),
+        }
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file.escrow_path)
+            .and_then(|mut escrow| escrow.write_all(line.as_bytes()))
+            .map_err(map_error)
+    }
+}
+
+/// Returns the default path of the escrow file. It's stored in `OUT_DIR` if
+/// the crate has a build script, otherwise in `CARGO_TARGET_DIR`. The compiler
+/// may run in any directory, so a relative `target` directory isn't used.
+fn default_path() -> syn::Result<PathBuf> {
+    std::env::var_os("OUT_DIR")
+        .or_else(|| std::env::var_os("CARGO_TARGET_DIR"))
+        .map(|dir| PathBuf::from(dir).join(ESCROW_FILE_NAME))
+        .ok_or_else(|| {
+            error_mapping(format!(
+                "Neither `OUT_DIR` nor `CARGO_TARGET_DIR` is set. Set `{}` to the path of the escrow file.",
+                ESCROW_VARIABLE
+            ))
+        })
+}
+
+/// Encodes the string as JSON string.
+fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+
+    json
+}
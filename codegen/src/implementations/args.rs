@@ -1,7 +1,47 @@
-use include_crypt_crypto::key::EncryptionKey;
-use proc_macro2::Span;
-use std::convert::TryFrom;
-use syn::parse::{Parse, ParseBuffer};
+use crate::{compression::Compression, implementations::cipher::Encrypted, utils::read_file_raw};
+use include_crypt_crypto::{
+    aes::Aes,
+    aes_gcm::AesGcm,
+    chacha::ChaCha,
+    cipher::Cipher,
+    compression::Algorithm,
+    envelope::MASTER_KEY_LEN,
+    kdf::Kdf,
+    key::EncryptionKey,
+    keyring::Keyring,
+    provider::EnvProvider,
+    xor::{Xor, XorStream},
+};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::{convert::TryFrom, fmt};
+use syn::{
+    parse::{Parse, ParseBuffer, ParseStream},
+    punctuated::Punctuated,
+};
+
+/// Parses the `= algorithm(level)` of the `compress` option.
+fn parse_compress(input: ParseStream<'_>) -> syn::Result<Compression> {
+    let _ = input.parse::<syn::Token![=]>()?;
+    input.parse::<Compression>()
+}
+
+/// Parses the optional `mac` flag and `compress` option at the end of the
+/// arguments.
+fn parse_options(input: ParseStream<'_>) -> syn::Result<(bool, Compression)> {
+    let mut mac = false;
+    let mut compression = Compression::default();
+    while input.parse::<syn::Token![,]>().is_ok() {
+        let ident = input.parse::<syn::Ident>()?;
+        match ident.to_string().as_str() {
+            "mac" => mac = true,
+            "compress" => compression = parse_compress(input)?,
+            _ => return Err(syn::Error::new(ident.span(), "Expected `mac` or `compress`")),
+        }
+    }
+
+    Ok((mac, compression))
+}
 
 /// Arguments for the file encryption implementations.
 ///
@@ -20,33 +60,852 @@ pub(crate) struct FileArgs {
     /// The parsed file path.
     pub file_path: String,
 
-    /// The parsed encryption key.
-    pub key: EncryptionKey,
+    /// The parsed encryption key. If it's not specified, it will be generated
+    /// while encrypting the file.
+    pub key: Option<EncryptionKey>,
 }
 
 impl Parse for FileArgs {
     fn parse(input: &'_ ParseBuffer<'_>) -> syn::parse::Result<Self> {
         let file_path = input.parse::<syn::LitStr>()?;
 
-        // If there's no key defined, generate one randomly.
+        // If there's no key defined, it will be generated later.
         //
         let key = if input.parse::<syn::Token![,]>().is_err() {
-            EncryptionKey::default()
+            None
+        } else {
+            Some(
+                EncryptionKey::try_from(&*input.parse::<syn::LitInt>()?.to_string())
+                    .map_err(|e| syn::parse::Error::new(Span::call_site(), e))?,
+            )
+        };
+
+        Ok(Self {
+            file_path: file_path.value(),
+            key,
+        })
+    }
+}
+
+/// Arguments for the custom cipher implementation.
+///
+/// # Example
+///
+/// ```text
+/// my_crate::MyCipher, "file.sealed"
+/// ```
+pub(crate) struct CipherArgs {
+    /// The path to the type that implements the `Cipher` trait.
+    pub cipher: syn::Path,
+
+    /// The parsed path of the sealed file.
+    pub file_path: String,
+}
+
+impl Parse for CipherArgs {
+    fn parse(input: &'_ ParseBuffer<'_>) -> syn::parse::Result<Self> {
+        let cipher = input.parse::<syn::Path>()?;
+        let _ = input.parse::<syn::Token![,]>()?;
+        let file_path = input.parse::<syn::LitStr>()?;
+
+        Ok(Self {
+            cipher,
+            file_path: file_path.value(),
+        })
+    }
+}
+
+/// The environment variable which contains the path of the default keyring.
+pub(crate) const KEYRING_VARIABLE: &str = "INCLUDE_CRYPT_KEYRING";
+
+/// Looks up the key of the key id in the keyring, or reads it from the same
+/// environment variable as the `EnvProvider` if there's no keyring. If no
+/// keyring is specified, the keyring from the `INCLUDE_CRYPT_KEYRING`
+/// environment variable is used.
+fn lookup_key(key_id: &str, keyring: Option<String>) -> syn::Result<String> {
+    let keyring = keyring.or_else(|| std::env::var(KEYRING_VARIABLE).ok().filter(|path| !path.is_empty()));
+
+    match keyring {
+        Some(keyring_path) => {
+            let keyring = String::from_utf8(read_file_raw(&keyring_path)?)
+                .map_err(|_| syn::Error::new(Span::call_site(), "The keyring file is not valid utf-8"))?
+                .parse::<Keyring>()
+                .map_err(|e| syn::Error::new(Span::call_site(), format!("{:?}: {}", keyring_path, e)))?;
+
+            keyring.get(key_id).map(|key| key.as_str()).ok_or_else(|| {
+                syn::Error::new(
+                    Span::call_site(),
+                    format!(
+                        "The key id {:?} is not in the keyring {:?}. Known key ids: {:?}",
+                        key_id,
+                        keyring_path,
+                        keyring.key_ids().collect::<Vec<_>>()
+                    ),
+                )
+            })
+        }
+        None => std::env::var(EnvProvider::var_name(key_id)).map_err(|_| {
+            syn::Error::new(
+                Span::call_site(),
+                format!(
+                    "No key for the key id {:?} found. Set the `{}` environment variable or specify the key.",
+                    key_id,
+                    EnvProvider::var_name(key_id)
+                ),
+            )
+        }),
+    }
+}
+
+/// Arguments for the implementation that embeds the file without the key.
+///
+/// # Example
+///
+/// The key is either specified explicitly, looked up in a keyring file or read
+/// from the environment variable of the key id (see `EnvProvider::var_name`).
+/// If no keyring is specified, the keyring from the `INCLUDE_CRYPT_KEYRING`
+/// environment variable is used:
+/// ```text
+/// AES, "file.txt", 0xdeadbeef, key_id = "my-key"
+/// AES, "file.txt", key_id = "2026-q3", keyring = "keys/keyring.toml"
+/// AES, "file.txt", key_id = "my-key", compress = zstd, mac
+/// ```
+pub(crate) struct ExternalArgs {
+    /// The encryption type which should be used to encrypt the file.
+    pub encryption_type: EncryptionType,
+
+    /// The parsed file path.
+    pub file_path: String,
+
+    /// The parsed encryption key with the length of the cipher.
+    pub key: EncryptionKey,
+
+    /// The id which is used to request the key at runtime.
+    pub key_id: String,
+
+    /// Whether the mac of the encrypted file should be embedded.
+    pub mac: bool,
+
+    /// The compression algorithm of the file.
+    pub compression: Compression,
+}
+
+impl Parse for ExternalArgs {
+    fn parse(input: &'_ ParseBuffer<'_>) -> syn::parse::Result<Self> {
+        let encryption_type = input.parse::<syn::Ident>()?;
+        let encryption_type = EncryptionType::try_from(encryption_type.to_string())
+            .map_err(|_| syn::Error::new(encryption_type.span(), "Invalid encryption type"))?;
+        let cipher = encryption_type
+            .cipher()
+            .ok_or_else(|| syn::Error::new(Span::mixed_site(), "Invalid encryption type"))?;
+
+        let _ = input.parse::<syn::Token![,]>()?;
+        let file_path = input.parse::<syn::LitStr>()?;
+        let _ = input.parse::<syn::Token![,]>()?;
+
+        // The key is optional and has to be specified before the key id.
+        //
+        let key = if input.peek(syn::LitInt) {
+            let key = input.parse::<syn::LitInt>()?.to_string();
+            let _ = input.parse::<syn::Token![,]>()?;
+            Some(key)
         } else {
-            EncryptionKey::try_from(&*input.parse::<syn::LitInt>()?.to_string())
-                .map_err(|e| syn::parse::Error::new(Span::call_site(), e))?
+            None
         };
 
+        let ident = input.parse::<syn::Ident>()?;
+        if ident != "key_id" {
+            return Err(syn::Error::new(ident.span(), "Expected `key_id`"));
+        }
+        let _ = input.parse::<syn::Token![=]>()?;
+        let key_id = input.parse::<syn::LitStr>()?.value();
+
+        let mut keyring = None;
+        let mut mac = false;
+        let mut compression = Compression::default();
+        while input.parse::<syn::Token![,]>().is_ok() {
+            let ident = input.parse::<syn::Ident>()?;
+            match ident.to_string().as_str() {
+                "keyring" => {
+                    let _ = input.parse::<syn::Token![=]>()?;
+                    keyring = Some(input.parse::<syn::LitStr>()?.value());
+                }
+                "mac" => mac = true,
+                "compress" => compression = parse_compress(input)?,
+                _ => return Err(syn::Error::new(ident.span(), "Expected `keyring`, `mac` or `compress`")),
+            }
+        }
+
+        let key = match key {
+            Some(key) => key,
+            None => lookup_key(&key_id, keyring)?,
+        };
+        let key = EncryptionKey::new(key.trim(), cipher.key_len())
+            .map_err(|e| syn::parse::Error::new(Span::call_site(), e))?;
+
         Ok(Self {
+            encryption_type,
             file_path: file_path.value(),
             key,
+            key_id,
+            mac,
+            compression,
+        })
+    }
+}
+
+/// Arguments for the implementation that embeds the mac of the encrypted file
+/// or compresses it with a specific algorithm.
+///
+/// # Example
+///
+/// The key is optional and will be generated randomly if it's not specified:
+/// ```text
+/// XOR, "file.txt", mac
+/// AES, "file.txt", 0xdeadbeef, mac
+/// AES, "file.txt", compress = zstd(19), mac
+/// ```
+pub(crate) struct MacArgs {
+    /// The encryption type which should be used to encrypt the file.
+    pub encryption_type: EncryptionType,
+
+    /// The parsed file path.
+    pub file_path: String,
+
+    /// The parsed encryption key with the length of the cipher. If it's not
+    /// specified, it will be generated while encrypting the file.
+    pub key: Option<EncryptionKey>,
+
+    /// Whether the mac of the encrypted file should be embedded.
+    pub mac: bool,
+
+    /// The compression algorithm of the file.
+    pub compression: Compression,
+}
+
+impl Parse for MacArgs {
+    fn parse(input: &'_ ParseBuffer<'_>) -> syn::parse::Result<Self> {
+        let encryption_type = input.parse::<syn::Ident>()?;
+        let encryption_type = EncryptionType::try_from(encryption_type.to_string())
+            .map_err(|_| syn::Error::new(encryption_type.span(), "Invalid encryption type"))?;
+        let cipher = encryption_type
+            .cipher()
+            .ok_or_else(|| syn::Error::new(Span::mixed_site(), "Invalid encryption type"))?;
+
+        let _ = input.parse::<syn::Token![,]>()?;
+        let file_path = input.parse::<syn::LitStr>()?;
+
+        // The key is optional and has to be specified before the options.
+        //
+        let key = if input.peek(syn::Token![,]) && input.peek2(syn::LitInt) {
+            let _ = input.parse::<syn::Token![,]>()?;
+            let key = input.parse::<syn::LitInt>()?.to_string();
+
+            Some(EncryptionKey::new(&key, cipher.key_len()).map_err(|e| syn::parse::Error::new(Span::call_site(), e))?)
+        } else {
+            None
+        };
+        let (mac, compression) = parse_options(input)?;
+
+        Ok(Self {
+            encryption_type,
+            file_path: file_path.value(),
+            key,
+            mac,
+            compression,
+        })
+    }
+}
+
+/// The master key which wraps the data key of an envelope.
+pub(crate) enum MasterKey {
+    /// The master key has been read from a file and is embedded into the
+    /// binary.
+    Embedded(EncryptionKey),
+
+    /// The master key is not embedded and has to be supplied by a
+    /// `KeyProvider` at runtime.
+    External { key_id: String, key: EncryptionKey },
+}
+
+impl MasterKey {
+    /// Returns the master key.
+    pub(crate) fn key(&self) -> &EncryptionKey {
+        match self {
+            MasterKey::Embedded(key) => key,
+            MasterKey::External { key, .. } => key,
+        }
+    }
+}
+
+/// The trailing options of the envelope and folder implementations, which can
+/// be specified in any order.
+///
+/// # Example
+///
+/// The master key is either read from a file or looked up by its id like the
+/// key of the external implementation:
+/// ```text
+/// master_key_file = "master.key", mac
+/// master_key_id = "master-2026", keyring = "keys/keyring.toml", compress = lz4
+/// ```
+#[derive(Default)]
+struct EnvelopeOptions {
+    /// The file which contains the hex encoded master key.
+    master_key_file: Option<syn::LitStr>,
+
+    /// The id which is used to request the master key at runtime.
+    master_key_id: Option<String>,
+
+    /// The keyring file which contains the master key of the id.
+    keyring: Option<String>,
+
+    /// Whether the mac of the encrypted file should be embedded.
+    mac: bool,
+
+    /// The compression algorithm of the files.
+    compression: Option<Compression>,
+
+    /// Whether the files of a folder should be combined into one archive.
+    solid: bool,
+}
+
+impl EnvelopeOptions {
+    /// Parses the remaining `, option` arguments.
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let mut options = Self::default();
+        while input.parse::<syn::Token![,]>().is_ok() {
+            let ident = input.parse::<syn::Ident>()?;
+            if ident == "mac" {
+                options.mac = true;
+                continue;
+            }
+            if ident == "compress" {
+                options.compression = Some(parse_compress(input)?);
+                continue;
+            }
+            if ident == "solid" {
+                options.solid = true;
+                continue;
+            }
+
+            let _ = input.parse::<syn::Token![=]>()?;
+            let value = input.parse::<syn::LitStr>()?;
+            match ident.to_string().as_str() {
+                "master_key_file" => options.master_key_file = Some(value),
+                "master_key_id" => options.master_key_id = Some(value.value()),
+                "keyring" => options.keyring = Some(value.value()),
+                _ => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "Expected `mac`, `compress`, `master_key_file`, `master_key_id` or `keyring`",
+                    ))
+                }
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Returns an error if `solid` has been specified for a single file.
+    fn check_file(&self) -> syn::Result<()> {
+        if self.solid {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`solid` can only be used by `include_dir!`",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Reads the master key from the file or looks up the key of the master
+    /// key id. If neither is specified, `None` will be returned.
+    fn master_key(&self) -> syn::Result<Option<MasterKey>> {
+        match (&self.master_key_file, &self.master_key_id) {
+            (Some(path), None) => {
+                let key = String::from_utf8(read_file_raw(path.value())?)
+                    .map_err(|_| syn::Error::new(path.span(), "The master key file is not valid utf-8"))?;
+                let key = EncryptionKey::from_hex(key.trim()).map_err(|e| syn::Error::new(path.span(), e))?;
+                if key.len() != MASTER_KEY_LEN {
+                    return Err(syn::Error::new(
+                        path.span(),
+                        format!("The master key has to be {} bytes", MASTER_KEY_LEN),
+                    ));
+                }
+
+                Ok(Some(MasterKey::Embedded(key)))
+            }
+            (None, Some(key_id)) => {
+                let key = lookup_key(key_id, self.keyring.clone())?;
+                let key = EncryptionKey::new(key.trim(), MASTER_KEY_LEN)
+                    .map_err(|e| syn::parse::Error::new(Span::call_site(), e))?;
+
+                Ok(Some(MasterKey::External {
+                    key_id: key_id.clone(),
+                    key,
+                }))
+            }
+            (Some(path), Some(_)) => Err(syn::Error::new(
+                path.span(),
+                "Expected either `master_key_file` or `master_key_id`",
+            )),
+            (None, None) if self.keyring.is_some() => {
+                Err(syn::Error::new(Span::call_site(), "`keyring` requires `master_key_id`"))
+            }
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+/// Arguments for the envelope implementation.
+///
+/// # Example
+///
+/// The data key is always generated. Only the master key which wraps it is
+/// specified:
+/// ```text
+/// AES_GCM, "file.txt", master_key_file = "master.key"
+/// AES, "file.txt", master_key_id = "master-2026", mac
+/// ```
+pub(crate) struct EnvelopeArgs {
+    /// The encryption type which should be used to encrypt the file.
+    pub encryption_type: EncryptionType,
+
+    /// The parsed file path.
+    pub file_path: String,
+
+    /// The master key which wraps the data key.
+    pub master_key: MasterKey,
+
+    /// Whether the mac of the encrypted file should be embedded.
+    pub mac: bool,
+
+    /// The compression algorithm of the file.
+    pub compression: Compression,
+}
+
+impl Parse for EnvelopeArgs {
+    fn parse(input: &'_ ParseBuffer<'_>) -> syn::parse::Result<Self> {
+        let encryption_type = input.parse::<syn::Ident>()?;
+        let encryption_type = EncryptionType::try_from(encryption_type.to_string())
+            .map_err(|_| syn::Error::new(encryption_type.span(), "Invalid encryption type"))?;
+
+        let _ = input.parse::<syn::Token![,]>()?;
+        let file_path = input.parse::<syn::LitStr>()?;
+
+        let options = EnvelopeOptions::parse(input)?;
+        options.check_file()?;
+        let master_key = options.master_key()?.ok_or_else(|| {
+            syn::Error::new(
+                Span::call_site(),
+                "Expected the master key `master_key_file` or `master_key_id`",
+            )
+        })?;
+
+        Ok(Self {
+            encryption_type,
+            file_path: file_path.value(),
+            master_key,
+            mac: options.mac,
+            compression: options.compression.unwrap_or_default(),
+        })
+    }
+}
+
+/// Arguments for the implementation that wraps the data key for several
+/// recipients.
+///
+/// # Example
+///
+/// The keys of the recipients are looked up by their id like the key of the
+/// external implementation:
+/// ```text
+/// AES_GCM, "file.txt", recipients = ["customer-a", "customer-b"]
+/// AES, "file.txt", recipients = ["customer-a"], keyring = "keys/licenses.toml", mac
+/// ```
+pub(crate) struct RecipientsArgs {
+    /// The encryption type which should be used to encrypt the file.
+    pub encryption_type: EncryptionType,
+
+    /// The parsed file path.
+    pub file_path: String,
+
+    /// The recipients with the key which wraps the data key.
+    pub recipients: Vec<MasterKey>,
+
+    /// Whether the mac of the encrypted file should be embedded.
+    pub mac: bool,
+
+    /// The compression algorithm of the file.
+    pub compression: Compression,
+}
+
+impl Parse for RecipientsArgs {
+    fn parse(input: &'_ ParseBuffer<'_>) -> syn::parse::Result<Self> {
+        let encryption_type = input.parse::<syn::Ident>()?;
+        let encryption_type = EncryptionType::try_from(encryption_type.to_string())
+            .map_err(|_| syn::Error::new(encryption_type.span(), "Invalid encryption type"))?;
+
+        let _ = input.parse::<syn::Token![,]>()?;
+        let file_path = input.parse::<syn::LitStr>()?;
+        let _ = input.parse::<syn::Token![,]>()?;
+
+        let ident = input.parse::<syn::Ident>()?;
+        if ident != "recipients" {
+            return Err(syn::Error::new(ident.span(), "Expected `recipients`"));
+        }
+        let _ = input.parse::<syn::Token![=]>()?;
+        let content;
+        syn::bracketed!(content in input);
+        let key_ids = Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated(&content)?;
+        if key_ids.is_empty() {
+            return Err(syn::Error::new(ident.span(), "Expected at least one recipient"));
+        }
+
+        let options = EnvelopeOptions::parse(input)?;
+        options.check_file()?;
+        if options.master_key_file.is_some() || options.master_key_id.is_some() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Expected `keyring`, `mac` or `compress`",
+            ));
+        }
+
+        let mut recipients = Vec::new();
+        for (i, key_id) in key_ids.iter().enumerate() {
+            if key_ids.iter().take(i).any(|other| other.value() == key_id.value()) {
+                return Err(syn::Error::new(key_id.span(), "Duplicate recipient"));
+            }
+
+            let key = lookup_key(&key_id.value(), options.keyring.clone())?;
+            let key = EncryptionKey::new(key.trim(), MASTER_KEY_LEN).map_err(|e| syn::Error::new(key_id.span(), e))?;
+            recipients.push(MasterKey::External {
+                key_id: key_id.value(),
+                key,
+            });
+        }
+
+        Ok(Self {
+            encryption_type,
+            file_path: file_path.value(),
+            recipients,
+            mac: options.mac,
+            compression: options.compression.unwrap_or_default(),
+        })
+    }
+}
+
+/// Arguments for the cascade implementation.
+///
+/// # Example
+///
+/// The built-in encryption types are applied from left to right, each with its
+/// own random key:
+/// ```text
+/// XOR + AES, "file.txt"
+/// XOR_STREAM + AES + CHACHA, "file.txt", mac
+/// XOR + AES, "file.txt", compress = brotli
+/// ```
+pub(crate) struct CascadeArgs {
+    /// The encryption types of the layers in the order they are applied.
+    pub layers: Vec<EncryptionType>,
+
+    /// The parsed file path.
+    pub file_path: String,
+
+    /// Whether the mac of the encrypted file should be embedded.
+    pub mac: bool,
+
+    /// The compression algorithm of the file.
+    pub compression: Compression,
+}
+
+impl Parse for CascadeArgs {
+    fn parse(input: &'_ ParseBuffer<'_>) -> syn::parse::Result<Self> {
+        let mut layers = Vec::new();
+        loop {
+            let layer = input.parse::<syn::Ident>()?;
+            layers.push(
+                EncryptionType::try_from(layer.to_string())
+                    .map_err(|_| syn::Error::new(layer.span(), "Invalid encryption type"))?,
+            );
+
+            if input.parse::<syn::Token![+]>().is_err() {
+                break;
+            }
+        }
+        if layers.len() < 2 {
+            return Err(syn::Error::new(Span::call_site(), "Expected at least two layers"));
+        }
+
+        let _ = input.parse::<syn::Token![,]>()?;
+        let file_path = input.parse::<syn::LitStr>()?;
+        let (mac, compression) = parse_options(input)?;
+
+        Ok(Self {
+            layers,
+            file_path: file_path.value(),
+            mac,
+            compression,
+        })
+    }
+}
+
+/// Arguments for the password implementation.
+///
+/// # Example
+///
+/// The password is read from an environment variable or a file at compile
+/// time. The key derivation function is optional and defaults to argon2:
+/// ```text
+/// "file.txt", env = "MY_PASSWORD"
+/// "file.txt", file = "password.txt", kdf = "pbkdf2"
+/// "file.txt", env = "MY_PASSWORD", compress = zstd, mac
+/// ```
+pub(crate) struct PasswordArgs {
+    /// The parsed file path.
+    pub file_path: String,
+
+    /// The password which is used to derive the key.
+    pub password: String,
+
+    /// The key derivation function.
+    pub kdf: Kdf,
+
+    /// Whether the mac of the encrypted file should be embedded.
+    pub mac: bool,
+
+    /// The compression algorithm of the file.
+    pub compression: Compression,
+}
+
+impl Parse for PasswordArgs {
+    fn parse(input: &'_ ParseBuffer<'_>) -> syn::parse::Result<Self> {
+        let file_path = input.parse::<syn::LitStr>()?;
+
+        let mut password = None;
+        let mut kdf = Kdf::default();
+        let mut mac = false;
+        let mut compression = Compression::default();
+        while input.parse::<syn::Token![,]>().is_ok() {
+            let ident = input.parse::<syn::Ident>()?;
+            if ident == "mac" {
+                mac = true;
+                continue;
+            }
+            if ident == "compress" {
+                compression = parse_compress(input)?;
+                continue;
+            }
+
+            let _ = input.parse::<syn::Token![=]>()?;
+            let value = input.parse::<syn::LitStr>()?;
+
+            match ident.to_string().as_str() {
+                "env" => {
+                    password = Some(std::env::var(value.value()).map_err(|_| {
+                        syn::Error::new(
+                            value.span(),
+                            format!("Environment variable {:?} not found.", value.value()),
+                        )
+                    })?)
+                }
+                "file" => {
+                    let bytes = read_file_raw(value.value())?;
+                    let content = String::from_utf8(bytes)
+                        .map_err(|_| syn::Error::new(value.span(), "The password file is not valid utf-8"))?;
+
+                    password = Some(content.trim_end_matches(['\r', '\n']).to_string());
+                }
+                "kdf" => {
+                    kdf = match value.value().to_lowercase().as_str() {
+                        "argon2" => Kdf::ARGON2,
+                        "pbkdf2" => Kdf::PBKDF2,
+                        _ => return Err(syn::Error::new(value.span(), "Invalid key derivation function")),
+                    }
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "Expected `env`, `file`, `kdf`, `mac` or `compress`",
+                    ))
+                }
+            }
+        }
+
+        let password =
+            password.ok_or_else(|| syn::Error::new(Span::call_site(), "Expected the password `env` or `file`"))?;
+        if password.is_empty() {
+            return Err(syn::Error::new(Span::call_site(), "The password is empty"));
+        }
+
+        Ok(Self {
+            file_path: file_path.value(),
+            password,
+            kdf,
+            mac,
+            compression,
+        })
+    }
+}
+
+/// Arguments for the public key implementation.
+///
+/// # Example
+///
+/// The hex encoded public key of the recipient is either specified directly or
+/// read from a file:
+/// ```text
+/// "file.txt", public_key = 0xdeadbeef...
+/// "file.txt", public_key_file = "recipient.pub", compress = lz4, mac
+/// ```
+pub(crate) struct PublicKeyArgs {
+    /// The parsed file path.
+    pub file_path: String,
+
+    /// The public key of the recipient.
+    pub public_key: EncryptionKey,
+
+    /// Whether the mac of the encrypted file should be embedded.
+    pub mac: bool,
+
+    /// The compression algorithm of the file.
+    pub compression: Compression,
+}
+
+impl Parse for PublicKeyArgs {
+    fn parse(input: &'_ ParseBuffer<'_>) -> syn::parse::Result<Self> {
+        let file_path = input.parse::<syn::LitStr>()?;
+        let _ = input.parse::<syn::Token![,]>()?;
+
+        let ident = input.parse::<syn::Ident>()?;
+        let _ = input.parse::<syn::Token![=]>()?;
+        let public_key = match ident.to_string().as_str() {
+            "public_key" if input.peek(syn::LitInt) => input.parse::<syn::LitInt>()?.to_string(),
+            "public_key" => input.parse::<syn::LitStr>()?.value(),
+            "public_key_file" => {
+                let path = input.parse::<syn::LitStr>()?;
+                String::from_utf8(read_file_raw(path.value())?)
+                    .map_err(|_| syn::Error::new(path.span(), "The public key file is not valid utf-8"))?
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "Expected `public_key` or `public_key_file`",
+                ))
+            }
+        };
+        let public_key =
+            EncryptionKey::from_hex(public_key.trim()).map_err(|e| syn::parse::Error::new(Span::call_site(), e))?;
+
+        let (mac, compression) = parse_options(input)?;
+
+        Ok(Self {
+            file_path: file_path.value(),
+            public_key,
+            mac,
+            compression,
+        })
+    }
+}
+
+/// Arguments for the signature implementations.
+///
+/// # Example
+///
+/// The signing key file contains the hex encoded ed25519 secret key:
+/// ```text
+/// "file.txt", "signing.key"
+/// ```
+pub(crate) struct SignArgs {
+    /// The parsed path of the file or folder.
+    pub path: String,
+
+    /// The secret key which is used to sign the file.
+    pub signing_key: EncryptionKey,
+}
+
+impl Parse for SignArgs {
+    fn parse(input: &'_ ParseBuffer<'_>) -> syn::parse::Result<Self> {
+        let path = input.parse::<syn::LitStr>()?;
+        let _ = input.parse::<syn::Token![,]>()?;
+        let key_path = input.parse::<syn::LitStr>()?;
+
+        let signing_key = String::from_utf8(read_file_raw(key_path.value())?)
+            .map_err(|_| syn::Error::new(key_path.span(), "The signing key file is not valid utf-8"))?;
+        let signing_key =
+            EncryptionKey::from_hex(signing_key.trim()).map_err(|e| syn::Error::new(key_path.span(), e))?;
+
+        Ok(Self {
+            path: path.value(),
+            signing_key,
         })
     }
 }
 
 pub(crate) enum EncryptionType {
     Xor,
+    XorStream,
     Aes,
+    AesGcm,
+    ChaCha,
+
+    /// A custom cipher. The files have to be sealed ahead of time.
+    Custom(syn::Path),
+}
+
+impl EncryptionType {
+    /// Returns the built-in cipher. Custom ciphers can't be executed by the
+    /// proc macro, so `None` will be returned for them.
+    pub(crate) fn cipher(&self) -> Option<&'static dyn Cipher> {
+        match self {
+            EncryptionType::Xor => Some(&Xor),
+            EncryptionType::XorStream => Some(&XorStream),
+            EncryptionType::Aes => Some(&Aes),
+            EncryptionType::AesGcm => Some(&AesGcm),
+            EncryptionType::ChaCha => Some(&ChaCha),
+            EncryptionType::Custom(_) => None,
+        }
+    }
+
+    /// Returns the path to the built-in cipher in the `include_crypt` crate.
+    pub(crate) fn cipher_path(&self) -> TokenStream {
+        match self {
+            EncryptionType::Xor => quote!(include_crypt::crypto::xor::Xor),
+            EncryptionType::XorStream => quote!(include_crypt::crypto::xor::XorStream),
+            EncryptionType::Aes => quote!(include_crypt::crypto::aes::Aes),
+            EncryptionType::AesGcm => quote!(include_crypt::crypto::aes_gcm::AesGcm),
+            EncryptionType::ChaCha => quote!(include_crypt::crypto::chacha::ChaCha),
+            EncryptionType::Custom(cipher) => quote!(#cipher),
+        }
+    }
+
+    /// Creates the `EncryptedFile` for the encrypted file.
+    pub(crate) fn expand(&self, file: &Encrypted) -> TokenStream {
+        let bytes = file.bytes();
+        let encryption_type = self.expand_type(file);
+
+        quote!(include_crypt::EncryptedFile::new(#bytes, #encryption_type))
+    }
+
+    /// Creates the `EncryptionType` with the key material of the encrypted
+    /// file.
+    pub(crate) fn expand_type(&self, file: &Encrypted) -> TokenStream {
+        let (key, nonce, tag) = (file.key(), file.nonce(), file.tag());
+
+        match self {
+            EncryptionType::Xor => quote!(include_crypt::EncryptionType::Xor(#key)),
+            EncryptionType::XorStream => quote!(include_crypt::EncryptionType::XorStream(#key, #nonce)),
+            EncryptionType::Aes => quote!(include_crypt::EncryptionType::Aes(#key, #nonce)),
+            EncryptionType::AesGcm => quote!(include_crypt::EncryptionType::AesGcm(#key, #nonce, #tag)),
+            EncryptionType::ChaCha => quote!(include_crypt::EncryptionType::ChaCha(#key, #nonce, #tag)),
+            EncryptionType::Custom(cipher) => {
+                let (key, nonce) = (file.key_ref(), file.nonce_ref());
+                quote!(include_crypt::EncryptionType::Custom(&#cipher, #key, #nonce, #tag))
+            }
+        }
+    }
 }
 
 impl TryFrom<String> for EncryptionType {
@@ -55,22 +914,41 @@ impl TryFrom<String> for EncryptionType {
     fn try_from(value: String) -> Result<Self, Self::Error> {
         match value.to_lowercase().as_str() {
             "xor" => Ok(Self::Xor),
+            "xor_stream" => Ok(Self::XorStream),
             "aes" => Ok(Self::Aes),
+            "aes_gcm" => Ok(Self::AesGcm),
+            "chacha" => Ok(Self::ChaCha),
             _ => Err(()),
         }
     }
 }
 
-impl ToString for EncryptionType {
-    fn to_string(&self) -> String {
+impl fmt::Display for EncryptionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            EncryptionType::Xor => "Xor",
-            EncryptionType::Aes => "Aes",
+            EncryptionType::Xor => write!(f, "Xor"),
+            EncryptionType::XorStream => write!(f, "XorStream"),
+            EncryptionType::Aes => write!(f, "Aes"),
+            EncryptionType::AesGcm => write!(f, "AesGcm"),
+            EncryptionType::ChaCha => write!(f, "ChaCha"),
+            EncryptionType::Custom(cipher) => write!(f, "{}", quote!(#cipher)),
         }
-        .to_string()
     }
 }
 
+/// Arguments for the folder encryption implementation.
+///
+/// # Example
+///
+/// The encryption type is either the name of a built-in algorithm or the path
+/// to a custom cipher:
+/// ```text
+/// "XOR", "assets"
+/// "AES", "assets", mac
+/// "AES_GCM", "assets", master_key_file = "master.key"
+/// "CHACHA", "assets", compress = zstd(19)
+/// my_crate::MyCipher, "assets"
+/// ```
 pub(crate) struct FolderArgs {
     /// The encryption type which should be used to encrypt the files in the
     /// folder.
@@ -78,20 +956,64 @@ pub(crate) struct FolderArgs {
 
     /// The parsed folder path.
     pub folder_path: String,
+
+    /// Whether the mac of every encrypted file should be embedded.
+    pub mac: bool,
+
+    /// The optional master key which wraps the data key of the folder.
+    pub master_key: Option<MasterKey>,
+
+    /// The compression algorithm of the files.
+    pub compression: Compression,
+
+    /// Whether the files should be combined into one compressed and encrypted
+    /// archive.
+    pub solid: bool,
 }
 
 impl Parse for FolderArgs {
     fn parse(input: &'_ ParseBuffer<'_>) -> syn::parse::Result<Self> {
-        let encryption_type = input.parse::<syn::LitStr>()?;
+        let encryption_type = if input.peek(syn::LitStr) {
+            EncryptionType::try_from(input.parse::<syn::LitStr>()?.value())
+                .map_err(|_| syn::Error::new(Span::mixed_site(), "Invalid encryption type"))?
+        } else {
+            EncryptionType::Custom(input.parse::<syn::Path>()?)
+        };
         let _ = input.parse::<syn::Token![,]>()?;
         let folder_path = input.parse::<syn::LitStr>()?;
 
-        let encryption_type = EncryptionType::try_from(encryption_type.value())
-            .map_err(|_| syn::Error::new(Span::mixed_site(), "Invalid encryption type"))?;
+        let options = EnvelopeOptions::parse(input)?;
+        let master_key = options.master_key()?;
+        if master_key.is_some() && encryption_type.cipher().is_none() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Sealed files can't be encrypted with a data key",
+            ));
+        }
+        if options.compression.is_some() && encryption_type.cipher().is_none() {
+            return Err(syn::Error::new(Span::call_site(), "Sealed files can't be compressed"));
+        }
+        if options.solid && encryption_type.cipher().is_none() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Sealed files can't be combined into a solid archive",
+            ));
+        }
+        let compression = options.compression.unwrap_or_default();
+        if options.solid && compression.algorithm() == Algorithm::ZstdDictionary {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Solid archives are compressed at once and can't use `zstd_dict`",
+            ));
+        }
 
         Ok(Self {
             encryption_type,
             folder_path: folder_path.value(),
+            mac: options.mac,
+            master_key,
+            compression,
+            solid: options.solid,
         })
     }
 }
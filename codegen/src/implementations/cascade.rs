@@ -0,0 +1,46 @@
+use crate::{
+    implementations::{
+        args::CascadeArgs,
+        cipher::{with_mac, Encrypted},
+    },
+    utils::{read_file, KeyRng},
+};
+use proc_macro::TokenStream;
+use quote::quote;
+
+#[doc(hidden)]
+pub(crate) fn impl_encrypt_cascade(input: TokenStream) -> syn::Result<TokenStream> {
+    let args: CascadeArgs = syn::parse(input)?;
+
+    // The file is only compressed once and then encrypted by every layer with its
+    // own generated key.
+    //
+    let (mut data, escrow) = read_file(&args.file_path, args.compression)?;
+    let mut rng = KeyRng::new(&args.file_path, &data);
+    let mut layers = Vec::with_capacity(args.layers.len());
+    for (i, layer) in args.layers.iter().enumerate() {
+        let cipher = layer.cipher().unwrap();
+        let file = Encrypted::encrypt_data(cipher, data, None, rng.fork(), escrow.layer(i))?;
+
+        layers.push(layer.expand_type(&file));
+        data = file.into_data().0;
+    }
+
+    // Return the encrypted file with all the layers
+    //
+    let bytes = syn::LitByteStr::new(&data, proc_macro2::Span::call_site());
+    let mac = if args.mac {
+        with_mac(&data, &mut rng, &escrow)?
+    } else {
+        quote!()
+    };
+
+    Ok(quote!(include_crypt::EncryptedFile::new(
+        #bytes,
+        include_crypt::EncryptionType::Cascade({
+            const LAYERS: &[include_crypt::EncryptionType] = &[#(#layers),*];
+            LAYERS
+        })
+    )#mac)
+    .into())
+}
@@ -0,0 +1,21 @@
+use crate::{
+    compression::Compression,
+    implementations::{args::FileArgs, cipher::Encrypted},
+};
+use include_crypt_crypto::chacha::ChaCha;
+use proc_macro::TokenStream;
+
+#[doc(hidden)]
+pub(crate) fn impl_encrypt_chacha(input: TokenStream) -> syn::Result<TokenStream> {
+    let args: FileArgs = syn::parse(input)?;
+    let file = Encrypted::encrypt(&ChaCha, &args.file_path, args.key, Compression::default())?;
+
+    // Return the key, nonce, authentication tag and encrypted file
+    //
+    let key = file.key();
+    let nonce = file.nonce();
+    let tag = file.tag();
+    let bytes = file.bytes();
+
+    Ok(quote::quote!((#key, #nonce, #tag, #bytes)).into())
+}
@@ -0,0 +1,290 @@
+use crate::{
+    compression::Compression,
+    escrow::Escrow,
+    implementations::args::CipherArgs,
+    utils::{error_mapping, read_file, read_file_raw, KeyRng},
+};
+use include_crypt_crypto::{
+    cipher::{Cipher, Sealed},
+    key::EncryptionKey,
+    mac::{hmac_sha256, MAC_KEY_LEN},
+};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use rand::Rng;
+use std::cell::RefCell;
+
+/// An encrypted file together with the key material that is needed to decrypt
+/// it at runtime.
+pub(crate) struct Encrypted {
+    key: EncryptionKey,
+    nonce: EncryptionKey,
+    tag: Vec<u8>,
+    data: Vec<u8>,
+
+    /// The generator of the key shares and the mac key.
+    rng: RefCell<KeyRng>,
+
+    /// The escrow of the generated keys.
+    escrow: Escrow,
+}
+
+impl Encrypted {
+    /// Reads and compresses the file and encrypts it with the specified cipher
+    /// and key. If no key is specified, it will be generated together with the
+    /// nonce.
+    pub(crate) fn encrypt(
+        cipher: &dyn Cipher, file_path: &str, key: Option<EncryptionKey>, compression: Compression,
+    ) -> syn::Result<Self> {
+        let (data, escrow) = read_file(file_path, compression)?;
+        let rng = KeyRng::new(file_path, &data);
+
+        Self::encrypt_data(cipher, data, key, rng, escrow)
+    }
+
+    /// Encrypts the data with the specified cipher and key. If no key is
+    /// specified, it will be generated together with the nonce. The key and
+    /// nonce are derived with separate labels, so that the nonce doesn't depend
+    /// on whether the key has been specified. The nonce and the generated key
+    /// are written to the escrow, a specified key is not.
+    pub(crate) fn encrypt_data(
+        cipher: &dyn Cipher, data: Vec<u8>, key: Option<EncryptionKey>, mut rng: KeyRng, escrow: Escrow,
+    ) -> syn::Result<Self> {
+        let key = match key {
+            Some(key) => key,
+            None => {
+                let key = rng.derive("key", cipher.key_len());
+                escrow.record("key", &key)?;
+                key
+            }
+        };
+        let nonce = rng.derive("nonce", cipher.nonce_len());
+
+        Self::encrypt_parts(cipher, data, key, nonce, rng, escrow)
+    }
+
+    /// Encrypts the compressed file with the specified cipher, key and nonce,
+    /// e.g. if they have been derived from the folder key. The key and nonce
+    /// are written to the escrow.
+    pub(crate) fn encrypt_derived(
+        cipher: &dyn Cipher, data: Vec<u8>, key: EncryptionKey, nonce: EncryptionKey, rng: KeyRng, escrow: Escrow,
+    ) -> syn::Result<Self> {
+        escrow.record("key", &key)?;
+
+        Self::encrypt_parts(cipher, data, key, nonce, rng, escrow)
+    }
+
+    /// Encrypts the data and writes the nonce to the escrow.
+    fn encrypt_parts(
+        cipher: &dyn Cipher, mut data: Vec<u8>, key: EncryptionKey, nonce: EncryptionKey, rng: KeyRng, escrow: Escrow,
+    ) -> syn::Result<Self> {
+        let tag = cipher
+            .encrypt(data.as_mut_slice(), &key, &nonce)
+            .map_err(error_mapping)?;
+
+        escrow.record("nonce", &nonce)?;
+
+        Ok(Self {
+            key,
+            nonce,
+            tag,
+            data,
+            rng: RefCell::new(rng),
+            escrow,
+        })
+    }
+
+    /// Reads a file that has already been encrypted with
+    /// `include_crypt_crypto::cipher::seal`. The keys of sealed files are
+    /// never written to the escrow, because they haven't been generated by
+    /// the macro.
+    pub(crate) fn sealed(file_path: &str) -> syn::Result<Self> {
+        let data = read_file_raw(file_path)?;
+        let rng = KeyRng::new(file_path, &data);
+        let Sealed { key, nonce, tag, data } =
+            Sealed::parse(&data).ok_or_else(|| error_mapping(format!("File {:?} is not a sealed file.", file_path)))?;
+
+        Ok(Self {
+            key,
+            nonce,
+            tag,
+            data,
+            rng: RefCell::new(rng),
+            escrow: Escrow::disabled(),
+        })
+    }
+
+    /// Returns the obfuscated key as `SplitKey`.
+    pub(crate) fn key(&self) -> TokenStream { split(&self.key, &mut self.rng.borrow_mut()) }
+
+    /// Returns the obfuscated nonce as `SplitKey`.
+    pub(crate) fn nonce(&self) -> TokenStream { split(&self.nonce, &mut self.rng.borrow_mut()) }
+
+    /// Returns the obfuscated key as `&'static SplitKey`.
+    pub(crate) fn key_ref(&self) -> TokenStream { split_ref(&self.key, &mut self.rng.borrow_mut()) }
+
+    /// Returns the obfuscated nonce as `&'static SplitKey`.
+    pub(crate) fn nonce_ref(&self) -> TokenStream { split_ref(&self.nonce, &mut self.rng.borrow_mut()) }
+
+    /// Returns the obfuscated bytes as `&'static SplitKey`, e.g. for the path
+    /// of a file.
+    pub(crate) fn obfuscate_ref(&self, data: &[u8]) -> TokenStream {
+        split_ref(&EncryptionKey::from(data.to_vec()), &mut self.rng.borrow_mut())
+    }
+
+    /// Returns the authentication tag as byte string.
+    pub(crate) fn tag(&self) -> TokenStream {
+        let tag = syn::LitByteStr::new(&self.tag, Span::call_site());
+        quote!(#tag)
+    }
+
+    /// Returns the `with_mac` call which adds the mac of the encrypted data.
+    pub(crate) fn with_mac(&self) -> syn::Result<TokenStream> {
+        with_mac(&self.data, &mut self.rng.borrow_mut(), &self.escrow)
+    }
+
+    /// Returns the encrypted data and the generator, so that the data can be
+    /// encrypted again.
+    pub(crate) fn into_data(self) -> (Vec<u8>, KeyRng) { (self.data, self.rng.into_inner()) }
+
+    /// Returns the encrypted data as byte string.
+    pub(crate) fn bytes(&self) -> TokenStream {
+        let bytes = syn::LitByteStr::new(&self.data, Span::call_site());
+        quote!(#bytes)
+    }
+}
+
+#[doc(hidden)]
+pub(crate) fn impl_encrypt_with(input: proc_macro::TokenStream) -> syn::Result<proc_macro::TokenStream> {
+    let args: CipherArgs = syn::parse(input)?;
+    let file = Encrypted::sealed(&args.file_path)?;
+
+    // Make sure that the cipher implements the trait and return the key, nonce,
+    // authentication tag and encrypted file.
+    //
+    let cipher = args.cipher;
+    let key = file.key_ref();
+    let nonce = file.nonce_ref();
+    let tag = file.tag();
+    let bytes = file.bytes();
+
+    Ok(quote!({
+        const _: &dyn include_crypt::crypto::cipher::Cipher = &#cipher;
+        (#key, #nonce, #tag, #bytes)
+    })
+    .into())
+}
+
+/// Computes the mac of the encrypted data with a generated key and returns the
+/// `with_mac` call which adds it to the `EncryptedFile`.
+pub(crate) fn with_mac(data: &[u8], rng: &mut KeyRng, escrow: &Escrow) -> syn::Result<TokenStream> {
+    let key = rng.key(MAC_KEY_LEN);
+    escrow.record("mac_key", &key)?;
+
+    let mac = syn::LitByteStr::new(&hmac_sha256(data, &key), Span::call_site());
+    let key = split(&key, rng);
+
+    Ok(quote!(.with_mac(#key, #mac)))
+}
+
+/// The number of shares every key is split into.
+const KEY_SHARES: usize = 3;
+
+/// The operation that combines a share with the shares before it.
+#[derive(Clone, Copy)]
+enum Operation {
+    Xor,
+    Add,
+    Sub,
+}
+
+/// Splits the key into shares which are stored in separate statics. The shares
+/// are combined with randomly chosen operations and rotations, so that the
+/// arithmetic is different for every call site. The shares, operations and
+/// rotations are drawn from the generator of the file.
+///
+/// The key is `share_0`, which is combined with each of the other shares in
+/// order: `key[i] = share_0[i] op_1 share_1[(i + rot_1) % len] op_2 ...`.
+pub(crate) fn split(key: &EncryptionKey, rng: &mut KeyRng) -> TokenStream {
+    let len = key.len();
+    if len == 0 {
+        return quote!(include_crypt::SplitKey::new({
+            fn combine(_: &mut [u8; 0]) {}
+            combine
+        }));
+    }
+
+    let operations = (1..KEY_SHARES)
+        .map(|_| {
+            let operation = match rng.gen_range(0..3) {
+                0 => Operation::Xor,
+                1 => Operation::Add,
+                _ => Operation::Sub,
+            };
+
+            (operation, rng.gen_range(0..len))
+        })
+        .collect::<Vec<_>>();
+
+    // Generate the random shares and undo the operations in reverse order to
+    // find the first share.
+    //
+    let mut shares = (1..KEY_SHARES).map(|_| rng.key(len).to_vec()).collect::<Vec<_>>();
+    let mut first = key.to_vec();
+    for ((operation, rotation), share) in operations.iter().zip(&shares).rev() {
+        for (i, byte) in first.iter_mut().enumerate() {
+            let other = share[(i + rotation) % len];
+            *byte = match operation {
+                Operation::Xor => *byte ^ other,
+                Operation::Add => byte.wrapping_sub(other),
+                Operation::Sub => byte.wrapping_add(other),
+            };
+        }
+    }
+    shares.insert(0, first);
+
+    // Read the statics with volatile reads, so that the compiler can't combine
+    // the shares at compile time. The bytes are read one at a time and combined
+    // straight into the key, so that no copy of the shares is left on the stack.
+    //
+    let statics = (0..KEY_SHARES)
+        .map(|i| quote::format_ident!("SHARE_{}", i))
+        .collect::<Vec<_>>();
+    let shares = shares.iter().map(|share| quote!([#(#share),*]));
+
+    let first = &statics[0];
+    let combined = operations.iter().zip(&statics[1..]).fold(
+        quote!(unsafe { core::ptr::read_volatile(&#first[i]) }),
+        |combined, ((operation, rotation), share)| {
+            let other = quote!(unsafe { core::ptr::read_volatile(&#share[(i + #rotation) % #len]) });
+
+            match operation {
+                Operation::Xor => quote!((#combined ^ #other)),
+                Operation::Add => quote!(#combined.wrapping_add(#other)),
+                Operation::Sub => quote!(#combined.wrapping_sub(#other)),
+            }
+        },
+    );
+
+    quote!(include_crypt::SplitKey::new({
+        #(static #statics: [u8; #len] = #shares;)*
+
+        fn combine(key: &mut [u8; #len]) {
+            for i in 0..#len {
+                key[i] = #combined;
+            }
+        }
+        combine
+    }))
+}
+
+/// Splits the key and returns a `&'static SplitKey`.
+fn split_ref(key: &EncryptionKey, rng: &mut KeyRng) -> TokenStream {
+    let len = key.len();
+    let key = split(key, rng);
+
+    quote!({
+        const KEY: include_crypt::SplitKey<#len> = #key;
+        &KEY
+    })
+}
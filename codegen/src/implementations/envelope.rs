@@ -0,0 +1,102 @@
+use crate::{
+    escrow::Escrow,
+    implementations::{
+        args::{EncryptionType, EnvelopeArgs, MasterKey},
+        cipher::{split, Encrypted},
+    },
+    utils::{error_mapping, read_file, KeyRng},
+};
+use include_crypt_crypto::{aes_gcm::AES_GCM_NONCE_LEN, cipher::Cipher, envelope::wrap_key, key::EncryptionKey};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+
+/// A generated data key together with the `WrappedKey` that stores it wrapped
+/// by the master key.
+pub(crate) struct Envelope {
+    data_key: EncryptionKey,
+    wrapped: TokenStream,
+}
+
+impl Envelope {
+    /// Generates the data key and wraps it with the master key.
+    pub(crate) fn new(master_key: &MasterKey, key_len: usize, rng: &mut KeyRng) -> syn::Result<Self> {
+        let data_key = rng.key(key_len);
+        let wrapped = wrap(&data_key, master_key, rng)?;
+
+        Ok(Self { data_key, wrapped })
+    }
+
+    /// Returns a copy of the data key.
+    pub(crate) fn data_key(&self) -> EncryptionKey { EncryptionKey::from(self.data_key.to_vec()) }
+
+    /// Encrypts the data with the data key, which is written to the escrow
+    /// because it has been generated.
+    pub(crate) fn encrypt(
+        &self, cipher: &dyn Cipher, data: Vec<u8>, rng: KeyRng, escrow: Escrow,
+    ) -> syn::Result<Encrypted> {
+        escrow.record("key", &self.data_key)?;
+
+        Encrypted::encrypt_data(cipher, data, Some(self.data_key()), rng, escrow)
+    }
+
+    /// Returns the `static WRAPPED` item which all the files of the envelope
+    /// reference.
+    pub(crate) fn wrapped_static(&self) -> TokenStream {
+        let wrapped = &self.wrapped;
+        quote!(static WRAPPED: include_crypt::WrappedKey = #wrapped;)
+    }
+
+    /// Creates the `EncryptedFile` for a file that has been encrypted with the
+    /// data key. It references the `WRAPPED` static.
+    pub(crate) fn expand(encryption_type: &EncryptionType, file: &Encrypted) -> TokenStream {
+        let cipher = encryption_type.cipher_path();
+        let nonce = file.nonce_ref();
+        let tag = file.tag();
+        let bytes = file.bytes();
+
+        quote!(include_crypt::EncryptedFile::new(
+            #bytes,
+            include_crypt::EncryptionType::Envelope(&#cipher, &WRAPPED, #nonce, #tag)
+        ))
+    }
+}
+
+/// Wraps the data key with the master key and returns the `WrappedKey`. If the
+/// master key is embedded, it will be split like the other keys.
+pub(crate) fn wrap(data_key: &EncryptionKey, master_key: &MasterKey, rng: &mut KeyRng) -> syn::Result<TokenStream> {
+    let nonce = rng.key(AES_GCM_NONCE_LEN);
+    let wrapped = wrap_key(data_key, master_key.key(), &nonce).map_err(error_mapping)?;
+    let wrapped = syn::LitByteStr::new(&wrapped, Span::call_site());
+
+    Ok(match master_key {
+        MasterKey::Embedded(key) => {
+            let key = split(key, rng);
+            quote!(include_crypt::WrappedKey::Embedded(#key, #wrapped))
+        }
+        MasterKey::External { key_id, .. } => quote!(include_crypt::WrappedKey::External(#key_id, #wrapped)),
+    })
+}
+
+#[doc(hidden)]
+pub(crate) fn impl_encrypt_envelope(input: proc_macro::TokenStream) -> syn::Result<proc_macro::TokenStream> {
+    let args: EnvelopeArgs = syn::parse(input)?;
+    let cipher = args.encryption_type.cipher().unwrap();
+
+    let (data, escrow) = read_file(&args.file_path, args.compression)?;
+    let mut rng = KeyRng::new(&args.file_path, &data);
+    let envelope = Envelope::new(&args.master_key, cipher.key_len(), &mut rng)?;
+    let file = envelope.encrypt(cipher, data, rng, escrow)?;
+
+    // Only embed the wrapped data key, the nonce, the authentication tag and the
+    // encrypted file.
+    //
+    let wrapped = envelope.wrapped_static();
+    let encrypted_file = Envelope::expand(&args.encryption_type, &file);
+    let mac = if args.mac { file.with_mac()? } else { quote!() };
+
+    Ok(quote!({
+        #wrapped
+        #encrypted_file #mac
+    })
+    .into())
+}
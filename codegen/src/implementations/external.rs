@@ -0,0 +1,25 @@
+use crate::implementations::{args::ExternalArgs, cipher::Encrypted};
+use proc_macro::TokenStream;
+
+#[doc(hidden)]
+pub(crate) fn impl_encrypt_external(input: TokenStream) -> syn::Result<TokenStream> {
+    let args: ExternalArgs = syn::parse(input)?;
+    let cipher = args.encryption_type.cipher().unwrap();
+    let file = Encrypted::encrypt(cipher, &args.file_path, Some(args.key), args.compression)?;
+
+    // Only embed the key id, the nonce, the authentication tag and the encrypted
+    // file. The key has to be supplied at runtime.
+    //
+    let cipher = args.encryption_type.cipher_path();
+    let key_id = args.key_id;
+    let nonce = file.nonce_ref();
+    let tag = file.tag();
+    let bytes = file.bytes();
+    let mac = if args.mac { file.with_mac()? } else { quote::quote!() };
+
+    Ok(quote::quote!(include_crypt::EncryptedFile::new(
+        #bytes,
+        include_crypt::EncryptionType::External(&#cipher, #key_id, #nonce, #tag)
+    ) #mac)
+    .into())
+}
@@ -1,117 +1,236 @@
 use crate::{
+    compression::Compression,
+    escrow::Escrow,
     implementations::{
-        aes,
         args::{EncryptionType, FolderArgs},
-        xor,
+        cipher::{split, Encrypted},
+        envelope::Envelope,
     },
-    utils,
+    utils::{self, error_mapping, read_file_raw, KeyRng},
+};
+use include_crypt_crypto::{
+    archive::solid_archive,
+    cipher::Cipher,
+    compression::Algorithm,
+    kdf::{derive_file_key, FOLDER_KEY_LEN},
+    key::EncryptionKey,
 };
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use std::{
-    collections::hash_map::DefaultHasher,
-    hash::{Hash, Hasher},
-    path::PathBuf,
-};
+
+/// The keys of the files in the folder.
+enum FolderKeys {
+    /// The files have been sealed with a custom cipher and embed their own
+    /// keys.
+    Sealed,
+
+    /// The key and nonce of every file are derived from the folder key and the
+    /// relative path of the file, so that only the folder key is embedded.
+    Derived(&'static dyn Cipher, EncryptionKey, KeyRng),
+
+    /// All the files are encrypted with the same data key, so that it only has
+    /// to be unwrapped once per folder.
+    Envelope(&'static dyn Cipher, Envelope),
+}
+
+/// The ids from which the keys of the dictionary and the solid archive are
+/// derived. Paths can't contain a null byte, so they never collide with the
+/// relative paths of the files.
+const DICTIONARY_ID: &str = "\0dictionary";
+const ARCHIVE_ID: &str = "\0archive";
+
+impl FolderKeys {
+    /// Encrypts the compressed content of a file. Sealed files are read as
+    /// they are. The keys of derived files are derived from the relative path
+    /// of the file in the folder.
+    fn encrypt(&self, file_path: &str, path: &str, data: Vec<u8>, escrow: Escrow) -> syn::Result<Encrypted> {
+        let rng = KeyRng::new(file_path, &data);
+
+        match self {
+            FolderKeys::Sealed => Encrypted::sealed(file_path),
+            FolderKeys::Derived(cipher, folder_key, _) => {
+                let (key, nonce) =
+                    derive_file_key(folder_key, path, cipher.key_len(), cipher.nonce_len()).map_err(error_mapping)?;
+                Encrypted::encrypt_derived(*cipher, data, key, nonce, rng, escrow)
+            }
+            FolderKeys::Envelope(cipher, envelope) => envelope.encrypt(*cipher, data, rng, escrow),
+        }
+    }
+
+    /// Creates the `EncryptedFile` for an encrypted file of the folder. The
+    /// relative path of derived files is embedded obfuscated.
+    fn expand(&self, encryption_type: &EncryptionType, path: &str, file: &Encrypted) -> TokenStream2 {
+        match self {
+            FolderKeys::Sealed => encryption_type.expand(file),
+            FolderKeys::Derived(..) => {
+                let cipher = encryption_type.cipher_path();
+                let path_len = path.len();
+                let path = file.obfuscate_ref(path.as_bytes());
+                let tag = file.tag();
+                let bytes = file.bytes();
+
+                quote!(include_crypt::EncryptedFile::new(
+                    #bytes,
+                    include_crypt::EncryptionType::Derived(&#cipher, &FOLDER_KEY, #path, #path_len, #tag)
+                ))
+            }
+            FolderKeys::Envelope(..) => Envelope::expand(encryption_type, file),
+        }
+    }
+}
 
 pub(crate) fn impl_include_files(input: TokenStream) -> syn::Result<TokenStream> {
     let args: FolderArgs = syn::parse(input)?;
 
-    // Create the correct path to the file
+    let (file_paths, (relative_paths, paths)): (Vec<_>, (Vec<_>, Vec<_>)) = utils::read_folder(&args.folder_path)?
+        .into_iter()
+        .map(|(file_path, relative_path, path)| (file_path, (relative_path, path)))
+        .unzip();
+    let file_paths = file_paths
+        .iter()
+        .map(|file| file.display().to_string())
+        .collect::<Vec<_>>();
+
+    // The folder key and the data key are generated from the content of all the
+    // files. Sealed files are read when they are embedded.
     //
-    let folder_path = PathBuf::from(args.folder_path);
-    let folder_path = if folder_path.is_relative() {
-        PathBuf::from(
-            std::env::var("CARGO_MANIFEST_DIR").expect("Failed to fine 'CARGO_MANIFEST_DIR' environment variable"),
-        )
-        .join(folder_path)
+    let encryption_type = &args.encryption_type;
+    let contents = match encryption_type.cipher() {
+        Some(_) => file_paths.iter().map(read_file_raw).collect::<syn::Result<Vec<_>>>()?,
+        None => vec![Vec::new(); file_paths.len()],
+    };
+    let mut keys = match encryption_type.cipher() {
+        Some(cipher) => {
+            let mut context = Vec::new();
+            for (file_path, content) in file_paths.iter().zip(&contents) {
+                context.extend(utils::relative_path(file_path).into_bytes());
+                context.push(0);
+                context.extend(content);
+            }
+            let mut rng = KeyRng::new(&args.folder_path, &context);
+
+            match &args.master_key {
+                Some(master_key) => {
+                    FolderKeys::Envelope(cipher, Envelope::new(master_key, cipher.key_len(), &mut rng)?)
+                }
+                None => FolderKeys::Derived(cipher, rng.key(FOLDER_KEY_LEN), rng),
+            }
+        }
+        None => FolderKeys::Sealed,
+    };
+
+    let (files, statics) = if args.solid {
+        encrypt_archive(&keys, &args, &paths, &contents)?
     } else {
-        folder_path
+        encrypt_files(&keys, &args, &file_paths, &relative_paths, contents)?
     };
 
-    // Check if the folder exists
+    // Create an array of encrypted files with their name:
+    // [ (name, file), (name, file) ]
     //
-    if !folder_path.exists() {
-        return Err(utils::error_mapping(format!(
-            "Folder {:?} could not be found.",
-            folder_path
-        )));
+    let files = quote!([ #((#paths, #files),)* ]);
+    let files = match &mut keys {
+        FolderKeys::Sealed => files,
+        FolderKeys::Derived(_, folder_key, rng) => {
+            let folder_key = split(folder_key, rng);
+            quote!({
+                static FOLDER_KEY: include_crypt::SplitKey<#FOLDER_KEY_LEN> = #folder_key;
+                #statics
+                #files
+            })
+        }
+        FolderKeys::Envelope(_, envelope) => {
+            let wrapped = envelope.wrapped_static();
+            quote!({
+                #wrapped
+                #statics
+                #files
+            })
+        }
     };
 
-    // Find the paths of all the files in the folder
-    //
-    let file_paths = glob::glob(format!("{}/**/*", folder_path.display()).as_str())
-        .map_err(utils::error_mapping)?
-        .filter_map(Result::ok)
-        .filter(|path| {
-            std::fs::metadata(path)
-                .map(|metadata| metadata.is_file())
-                .unwrap_or_default()
-        })
-        .collect::<Vec<_>>();
+    Ok(files.into())
+}
 
-    // Encrypt all the files
-    //
-    let encryption_type = args.encryption_type;
+/// Encrypts every file on its own. The files that have been compressed with
+/// the dictionary reference the `DICTIONARY` static, which is returned with
+/// the files.
+fn encrypt_files(
+    keys: &FolderKeys, args: &FolderArgs, file_paths: &[String], relative_paths: &[String], contents: Vec<Vec<u8>>,
+) -> syn::Result<(Vec<TokenStream2>, TokenStream2)> {
+    let (compression, dictionary) = args.compression.train(file_paths, &contents);
+    let mut uses_dictionary = false;
     let files = file_paths
-        .clone()
-        .into_iter()
-        .map(|file| file.display().to_string())
-        .map(|file| {
-            match &encryption_type {
-                EncryptionType::Xor => xor::impl_encrypt_xor(TokenStream::from(quote!(#file))),
-                EncryptionType::Aes => aes::impl_encrypt_aes(TokenStream::from(quote!(#file))),
-            }
-            .expect("Failed to encrypt file")
-        })
-        .map(proc_macro2::TokenStream::from)
-        .collect::<Vec<_>>();
+        .iter()
+        .zip(relative_paths)
+        .zip(contents)
+        .map(|((file_path, relative_path), content)| {
+            let escrow = Escrow::new(file_path, &content)?;
+            let data = compression
+                .for_file(file_path)
+                .compress_with(content, dictionary.as_deref())?;
+            let with_dictionary = if data.last() == Some(&Algorithm::ZstdDictionary.id()) {
+                uses_dictionary = true;
+                quote!(.with_dictionary(&DICTIONARY))
+            } else {
+                quote!()
+            };
 
-    // Convert the absolute paths into relative paths
-    //
-    let paths: Vec<String> = file_paths
-        .into_iter()
-        .map(|path| {
-            path.strip_prefix(&folder_path)
-                .map(|path| path.display().to_string())
-                .unwrap_or_default()
-        })
-        .map(|path| {
-            let path = path.replace("\\", "/");
+            let file = keys.encrypt(file_path, relative_path, data, escrow)?;
+            let mac = if args.mac { file.with_mac()? } else { quote!() };
+            let file = keys.expand(&args.encryption_type, relative_path, &file);
 
-            let mut hasher = DefaultHasher::new();
-            path.hash(&mut hasher);
-            hasher.finish().to_string()
+            Ok(quote!(#file #mac #with_dictionary))
         })
-        .collect::<Vec<_>>();
+        .collect::<syn::Result<Vec<_>>>()?;
 
-    // Create an array of encrypted files with their name:
-    // [ (name, file), (name, file) ]
+    // The dictionary is encrypted like the files, so its keys are written to the
+    // escrow like the keys of a file, with the `dictionary/` prefix.
     //
-    let expanded = match encryption_type {
-        EncryptionType::Xor => quote!(
-            [
-                #((
-                    #paths,
-                    {
-                        let (key, data) = #files;
-                        include_crypt::EncryptedFile::new(data, include_crypt::EncryptionType::Xor(key))
-                    }
-                ),)*
-            ]
-        ),
-        EncryptionType::Aes => quote!(
-            [
-                #((
-                    #paths,
-                    {
-                        let (key, nonce, data) = #files;
-                        include_crypt::EncryptedFile::new(data, include_crypt::EncryptionType::Aes(key, nonce))
-                    }
-                ),)*
-            ]
-        ),
+    let dictionary = match dictionary {
+        Some(dictionary) if uses_dictionary => {
+            let escrow = Escrow::new(&args.folder_path, &dictionary)?.prefixed("dictionary/");
+            let data = Compression::NONE.compress(dictionary)?;
+            let file = keys.encrypt(&args.folder_path, DICTIONARY_ID, data, escrow)?;
+            let mac = if args.mac { file.with_mac()? } else { quote!() };
+            let file = keys.expand(&args.encryption_type, DICTIONARY_ID, &file);
+
+            quote!(static DICTIONARY: include_crypt::EncryptedFile = #file #mac;)
+        }
+        _ => quote!(),
     };
 
-    Ok(expanded.into())
+    Ok((files, dictionary))
+}
+
+/// Combines all the files into one archive, which is compressed and encrypted
+/// at once. The index with the ids of the files is part of the encrypted
+/// archive and the files only reference the `ARCHIVE` static, which is
+/// returned with the files.
+fn encrypt_archive(
+    keys: &FolderKeys, args: &FolderArgs, paths: &[String], contents: &[Vec<u8>],
+) -> syn::Result<(Vec<TokenStream2>, TokenStream2)> {
+    let archive = solid_archive(paths.iter().map(String::as_str).zip(contents.iter().map(Vec::as_slice)));
+    let escrow = Escrow::new(&args.folder_path, &archive)?;
+
+    let data = args.compression.compress(archive)?;
+    let file = keys.encrypt(&args.folder_path, ARCHIVE_ID, data, escrow)?;
+    let mac = if args.mac { file.with_mac()? } else { quote!() };
+    let file = keys.expand(&args.encryption_type, ARCHIVE_ID, &file);
+
+    let files = paths
+        .iter()
+        .map(|file_id| {
+            quote!(include_crypt::EncryptedFile::new(
+                &[],
+                include_crypt::EncryptionType::Solid(&ARCHIVE, #file_id)
+            ))
+        })
+        .collect();
+
+    Ok((
+        files,
+        quote!(static ARCHIVE: include_crypt::SolidArchive = include_crypt::SolidArchive::new(#file #mac);),
+    ))
 }
@@ -0,0 +1,16 @@
+use crate::implementations::{args::MacArgs, cipher::Encrypted};
+use proc_macro::TokenStream;
+
+#[doc(hidden)]
+pub(crate) fn impl_encrypt_mac(input: TokenStream) -> syn::Result<TokenStream> {
+    let args: MacArgs = syn::parse(input)?;
+    let cipher = args.encryption_type.cipher().unwrap();
+    let file = Encrypted::encrypt(cipher, &args.file_path, args.key, args.compression)?;
+
+    // Return the encrypted file together with the mac of the encrypted buffer
+    //
+    let encrypted_file = args.encryption_type.expand(&file);
+    let mac = if args.mac { file.with_mac()? } else { quote::quote!() };
+
+    Ok(quote::quote!(#encrypted_file #mac).into())
+}
@@ -1,4 +1,15 @@
 pub(crate) mod aes;
+pub(crate) mod aes_gcm;
 pub(crate) mod args;
+pub(crate) mod cascade;
+pub(crate) mod chacha;
+pub(crate) mod cipher;
+pub(crate) mod envelope;
+pub(crate) mod external;
 pub(crate) mod files;
+pub(crate) mod mac;
+pub(crate) mod password;
+pub(crate) mod recipients;
+pub(crate) mod signature;
+pub(crate) mod x25519;
 pub(crate) mod xor;
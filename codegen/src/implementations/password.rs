@@ -0,0 +1,50 @@
+use crate::{
+    implementations::{args::PasswordArgs, cipher::Encrypted},
+    utils::{error_mapping, read_file, KeyRng},
+};
+use include_crypt_crypto::{
+    aes_gcm::{AesGcm, AES_GCM_KEY_LEN},
+    kdf::{Kdf, KDF_SALT_LEN},
+};
+use proc_macro::TokenStream;
+use quote::quote;
+
+#[doc(hidden)]
+pub(crate) fn impl_encrypt_password(input: TokenStream) -> syn::Result<TokenStream> {
+    let args: PasswordArgs = syn::parse(input)?;
+
+    // Derive the key from the password with a generated salt. Only the salt and
+    // the parameters of the key derivation function will be embedded.
+    //
+    let (data, escrow) = read_file(&args.file_path, args.compression)?;
+    let mut rng = KeyRng::new(&args.file_path, &data);
+    let salt = rng.key(KDF_SALT_LEN);
+    let key = args
+        .kdf
+        .derive(args.password.as_bytes(), &salt, AES_GCM_KEY_LEN)
+        .map_err(error_mapping)?;
+    let file = Encrypted::encrypt_data(&AesGcm, data, Some(key), rng, escrow)?;
+
+    // Return the file with the key derivation function, salt, nonce and
+    // authentication tag
+    //
+    let kdf = match args.kdf {
+        Kdf::Argon2 { m_cost, t_cost, p_cost } => quote!(include_crypt::crypto::kdf::Kdf::Argon2 {
+            m_cost: #m_cost,
+            t_cost: #t_cost,
+            p_cost: #p_cost,
+        }),
+        Kdf::Pbkdf2 { rounds } => quote!(include_crypt::crypto::kdf::Kdf::Pbkdf2 { rounds: #rounds }),
+    };
+    let salt = syn::LitByteStr::new(&salt, proc_macro2::Span::call_site());
+    let nonce = file.nonce();
+    let tag = file.tag();
+    let bytes = file.bytes();
+    let mac = if args.mac { file.with_mac()? } else { quote!() };
+
+    Ok(quote!(include_crypt::EncryptedFile::new(
+        #bytes,
+        include_crypt::EncryptionType::Password(#kdf, #salt, #nonce, #tag)
+    ) #mac)
+    .into())
+}
@@ -0,0 +1,45 @@
+use crate::{
+    implementations::{args::RecipientsArgs, cipher::Encrypted, envelope::wrap},
+    utils::{read_file, KeyRng},
+};
+use proc_macro::TokenStream;
+use quote::quote;
+
+#[doc(hidden)]
+pub(crate) fn impl_encrypt_recipients(input: TokenStream) -> syn::Result<TokenStream> {
+    let args: RecipientsArgs = syn::parse(input)?;
+    let cipher = args.encryption_type.cipher().unwrap();
+
+    // Wrap the data key once for every recipient
+    //
+    let (data, escrow) = read_file(&args.file_path, args.compression)?;
+    let mut rng = KeyRng::new(&args.file_path, &data);
+    let data_key = rng.key(cipher.key_len());
+    escrow.record("key", &data_key)?;
+    let recipients = args
+        .recipients
+        .iter()
+        .map(|recipient| wrap(&data_key, recipient, &mut rng))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let file = Encrypted::encrypt_data(cipher, data, Some(data_key), rng, escrow)?;
+
+    // Only embed the table of recipients, the nonce, the authentication tag and
+    // the encrypted file.
+    //
+    let cipher = args.encryption_type.cipher_path();
+    let len = recipients.len();
+    let nonce = file.nonce_ref();
+    let tag = file.tag();
+    let bytes = file.bytes();
+    let mac = if args.mac { file.with_mac()? } else { quote!() };
+
+    Ok(quote!({
+        static RECIPIENTS: [include_crypt::WrappedKey; #len] = [#(#recipients),*];
+
+        include_crypt::EncryptedFile::new(
+            #bytes,
+            include_crypt::EncryptionType::Recipients(&#cipher, &RECIPIENTS, #nonce, #tag)
+        ) #mac
+    })
+    .into())
+}
@@ -0,0 +1,44 @@
+use crate::{
+    implementations::args::SignArgs,
+    utils::{error_mapping, read_file_raw, read_folder},
+};
+use include_crypt_crypto::signature::{ed25519_manifest, ed25519_sign};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+
+/// Signs the data and returns the signature and public key.
+fn sign(data: &[u8], args: &SignArgs) -> syn::Result<TokenStream> {
+    let (signature, public_key) = ed25519_sign(data, &args.signing_key).map_err(error_mapping)?;
+
+    let signature = syn::LitByteStr::new(&signature, Span::call_site());
+    let public_key = syn::LitByteStr::new(public_key.as_bytes(), Span::call_site());
+
+    Ok(quote!((#signature, include_crypt::crypto::signature::PublicKey::new(*#public_key))).into())
+}
+
+#[doc(hidden)]
+pub(crate) fn impl_sign_file(input: TokenStream) -> syn::Result<TokenStream> {
+    let args: SignArgs = syn::parse(input)?;
+
+    // Sign the plaintext, so that the signature can be verified after decrypting
+    // the file.
+    //
+    sign(&read_file_raw(&args.path)?, &args)
+}
+
+#[doc(hidden)]
+pub(crate) fn impl_sign_files(input: TokenStream) -> syn::Result<TokenStream> {
+    let args: SignArgs = syn::parse(input)?;
+
+    // Sign the manifest of the hashed paths and the plaintexts in the same order
+    // as `include_files`.
+    //
+    let files = read_folder(&args.path)?
+        .into_iter()
+        .map(|(file_path, _, path)| read_file_raw(file_path).map(|data| (path, data)))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let manifest = ed25519_manifest(files.iter().map(|(path, data)| (path.as_str(), data.as_slice())));
+
+    sign(&manifest, &args)
+}
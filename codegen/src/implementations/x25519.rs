@@ -0,0 +1,44 @@
+use crate::{
+    implementations::{args::PublicKeyArgs, cipher::with_mac},
+    utils::{error_mapping, read_file, KeyRng},
+};
+use include_crypt_crypto::{
+    cipher::Cipher,
+    x25519::{x25519_public_key, X25519, X25519_KEY_LEN},
+};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+
+#[doc(hidden)]
+pub(crate) fn impl_encrypt_x25519(input: TokenStream) -> syn::Result<TokenStream> {
+    let args: PublicKeyArgs = syn::parse(input)?;
+
+    // Generate the ephemeral key pair and encrypt the file to the public key of
+    // the recipient.
+    //
+    let (mut data, escrow) = read_file(&args.file_path, args.compression)?;
+    let mut rng = KeyRng::new(&args.file_path, &data);
+    let secret = rng.key(X25519_KEY_LEN);
+    let ephemeral = x25519_public_key(&secret).unwrap();
+    let tag = X25519
+        .encrypt(data.as_mut_slice(), &secret, &args.public_key)
+        .map_err(|_| error_mapping("The public key is not a valid x25519 public key."))?;
+    escrow.record("ephemeral_secret_key", &secret)?;
+
+    // Return the file with the ephemeral public key and authentication tag
+    //
+    let mac = if args.mac {
+        with_mac(&data, &mut rng, &escrow)?
+    } else {
+        quote::quote!()
+    };
+    let ephemeral = syn::LitByteStr::new(&ephemeral, Span::call_site());
+    let tag = syn::LitByteStr::new(&tag, Span::call_site());
+    let bytes = syn::LitByteStr::new(&data, Span::call_site());
+
+    Ok(quote::quote!(include_crypt::EncryptedFile::new(
+        #bytes,
+        include_crypt::EncryptionType::X25519(#ephemeral, #tag)
+    ) #mac)
+    .into())
+}
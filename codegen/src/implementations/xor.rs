@@ -1,20 +1,33 @@
-use crate::{implementations::args::FileArgs, utils::read_file};
-use include_crypt_crypto::xor::xor;
+use crate::{
+    compression::Compression,
+    implementations::{args::FileArgs, cipher::Encrypted},
+};
+use include_crypt_crypto::xor::{Xor, XorStream};
 use proc_macro::TokenStream;
 
 #[doc(hidden)]
 pub(crate) fn impl_encrypt_xor(input: TokenStream) -> syn::Result<TokenStream> {
     let args: FileArgs = syn::parse(input)?;
-    let mut file = read_file(&args.file_path)?;
+    let file = Encrypted::encrypt(&Xor, &args.file_path, args.key, Compression::default())?;
 
-    // Encrypt the file
+    // Return the key and encrypted file
     //
-    xor(file.as_mut_slice(), &args.key);
+    let key = file.key();
+    let bytes = file.bytes();
 
-    // Return the key and encrypted file
+    Ok(quote::quote!((#key, #bytes)).into())
+}
+
+#[doc(hidden)]
+pub(crate) fn impl_encrypt_xor_stream(input: TokenStream) -> syn::Result<TokenStream> {
+    let args: FileArgs = syn::parse(input)?;
+    let file = Encrypted::encrypt(&XorStream, &args.file_path, args.key, Compression::default())?;
+
+    // Return the key, nonce and encrypted file
     //
-    let bytes = syn::LitByteStr::new(&file, proc_macro2::Span::call_site());
-    let key = args.key.as_str();
+    let key = file.key();
+    let nonce = file.nonce();
+    let bytes = file.bytes();
 
-    Ok(quote::quote!((include_crypt::obfstr::obfconst!(#key), #bytes)).into())
+    Ok(quote::quote!((#key, #nonce, #bytes)).into())
 }
@@ -1,6 +1,10 @@
-use implementations::{aes, files, xor};
+use implementations::{
+    aes, aes_gcm, cascade, chacha, cipher, envelope, external, files, mac, password, recipients, signature, x25519, xor,
+};
 use proc_macro::TokenStream;
 
+mod compression;
+mod escrow;
 mod implementations;
 mod utils;
 
@@ -23,12 +27,42 @@ mod utils;
 /// ```
 #[proc_macro]
 pub fn encrypt_xor(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_xor", &input);
+
     match xor::impl_encrypt_xor(input) {
         Ok(ts) => ts,
         Err(err) => err.to_compile_error().into(),
     }
 }
 
+/// Encrypts a file with a keystream that is generated from a random or custom
+/// key and a random nonce.
+///
+/// # Example
+///
+/// ## Custom key
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_xor_stream;
+/// let (key, nonce, encrypted) = encrypt_xor_stream!("src/lib.rs", 0xdeadbeef);
+/// ```
+///
+/// ## Random key
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_xor_stream;
+/// let (key, nonce, encrypted) = encrypt_xor_stream!("src/lib.rs");
+/// ```
+#[proc_macro]
+pub fn encrypt_xor_stream(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_xor_stream", &input);
+
+    match xor::impl_encrypt_xor_stream(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
 /// Encrypts a file with a random or custom key.
 ///
 /// # Example
@@ -48,12 +82,248 @@ pub fn encrypt_xor(input: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro]
 pub fn encrypt_aes(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_aes", &input);
+
     match aes::impl_encrypt_aes(input) {
         Ok(ts) => ts,
         Err(err) => err.to_compile_error().into(),
     }
 }
 
+/// Encrypts a file with a random or custom key and computes the
+/// authentication tag.
+///
+/// # Example
+///
+/// ## Custom key
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_aes_gcm;
+/// let (key, nonce, tag, encrypted) = encrypt_aes_gcm!("src/lib.rs", 0xdeadbeef);
+/// ```
+///
+/// ## Random key
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_aes_gcm;
+/// let (key, nonce, tag, encrypted) = encrypt_aes_gcm!("src/lib.rs");
+/// ```
+#[proc_macro]
+pub fn encrypt_aes_gcm(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_aes_gcm", &input);
+
+    match aes_gcm::impl_encrypt_aes_gcm(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Encrypts a file with a random or custom key using XChaCha20-Poly1305 and
+/// computes the authentication tag.
+///
+/// # Example
+///
+/// ## Custom key
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_chacha;
+/// let (key, nonce, tag, encrypted) = encrypt_chacha!("src/lib.rs", 0xdeadbeef);
+/// ```
+///
+/// ## Random key
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_chacha;
+/// let (key, nonce, tag, encrypted) = encrypt_chacha!("src/lib.rs");
+/// ```
+#[proc_macro]
+pub fn encrypt_chacha(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_chacha", &input);
+
+    match chacha::impl_encrypt_chacha(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Encrypts a file with several built-in encryption types in sequence. Each
+/// layer gets its own random key.
+///
+/// # Example
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_cascade;
+/// let file = encrypt_cascade!(XOR + AES, "src/lib.rs");
+/// ```
+#[proc_macro]
+pub fn encrypt_cascade(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_cascade", &input);
+
+    match cascade::impl_encrypt_cascade(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Encrypts a file with a random or custom key and embeds the HMAC-SHA256 of
+/// the encrypted buffer, so that a patched or corrupted buffer is detected
+/// before it is decrypted. The file can also be compressed with a specific
+/// algorithm.
+///
+/// # Example
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_mac;
+/// let file = encrypt_mac!(XOR, "src/lib.rs", 0xdeadbeef, mac);
+/// let file = encrypt_mac!(AES, "src/lib.rs", compress = none, mac);
+/// ```
+#[proc_macro]
+pub fn encrypt_mac(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_mac", &input);
+
+    match mac::impl_encrypt_mac(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Embeds a file that has been sealed with a custom cipher.
+///
+/// The proc macro can't execute the cipher, so the file has to be sealed with
+/// `include_crypt_crypto::cipher::seal` ahead of time (e.g. in a build script).
+/// The cipher path is only used to make sure that it implements the `Cipher`
+/// trait.
+///
+/// # Example
+///
+/// ```ignore
+/// # use include_crypt_codegen::encrypt_with;
+/// let (key, nonce, tag, encrypted) = encrypt_with!(my_crate::MyCipher, "file.sealed");
+/// ```
+#[proc_macro]
+pub fn encrypt_with(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_with", &input);
+
+    match cipher::impl_encrypt_with(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Encrypts a file without embedding the key. Only the key id is embedded, so
+/// that the key can be requested from a `KeyProvider` at runtime. If no key is
+/// specified, it will be read from the environment variable of the key id
+/// (e.g. `INCLUDE_CRYPT_KEY_MY_KEY`).
+///
+/// # Example
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_external;
+/// let file = encrypt_external!(AES, "src/lib.rs", 0xdeadbeef, key_id = "my-key");
+/// ```
+#[proc_macro]
+pub fn encrypt_external(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_external", &input);
+
+    match external::impl_encrypt_external(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Encrypts a file with a random data key, which is wrapped by a master key.
+/// The master key is either read from a file at compile time and embedded, or
+/// supplied by a `KeyProvider` at runtime. Rotating the master key only
+/// rewraps the data key.
+///
+/// # Example
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_envelope;
+/// let file = encrypt_envelope!(
+///     AES_GCM,
+///     "src/lib.rs",
+///     master_key_file = "../include-crypt/tests/master.key"
+/// );
+/// ```
+#[proc_macro]
+pub fn encrypt_envelope(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_envelope", &input);
+
+    match envelope::impl_encrypt_envelope(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Encrypts a file with a random data key, which is wrapped once for every
+/// recipient. The keys of the recipients are looked up by their id at compile
+/// time, so that the file can be decrypted with the key of any recipient.
+///
+/// # Example
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_recipients;
+/// let file = encrypt_recipients!(
+///     AES_GCM,
+///     "src/lib.rs",
+///     recipients = ["customer-a", "customer-b"],
+///     keyring = "../include-crypt/tests/keyring.toml"
+/// );
+/// ```
+#[proc_macro]
+pub fn encrypt_recipients(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_recipients", &input);
+
+    match recipients::impl_encrypt_recipients(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Encrypts a file with a key that is derived from a password. The password is
+/// read from an environment variable or a file at compile time. Only the salt
+/// and the parameters of the key derivation function are embedded.
+///
+/// # Example
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_password;
+/// let file = encrypt_password!("src/lib.rs", env = "CARGO_PKG_NAME", kdf = "pbkdf2");
+/// ```
+#[proc_macro]
+pub fn encrypt_password(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_password", &input);
+
+    match password::impl_encrypt_password(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Encrypts a file to the x25519 public key of the recipient. Only the
+/// ephemeral public key is embedded, so the file can only be decrypted with the
+/// secret key of the recipient.
+///
+/// # Example
+///
+/// ```
+/// # use include_crypt_codegen::encrypt_x25519;
+/// let file = encrypt_x25519!(
+///     "src/lib.rs",
+///     public_key = "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"
+/// );
+/// ```
+#[proc_macro]
+pub fn encrypt_x25519(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("encrypt_x25519", &input);
+
+    match x25519::impl_encrypt_x25519(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
 /// Encrypts all the files in the specified folder.
 ///
 /// # Example
@@ -62,10 +332,53 @@ pub fn encrypt_aes(input: TokenStream) -> TokenStream {
 /// # use include_crypt_codegen::include_files;
 /// let files = include_files!("XOR", "src");
 /// ```
+///
+/// The files in the folder can also be sealed with a custom cipher:
+///
+/// ```ignore
+/// # use include_crypt_codegen::include_files;
+/// let files = include_files!(my_crate::MyCipher, "sealed");
+/// ```
 #[proc_macro]
 pub fn include_files(input: TokenStream) -> TokenStream {
+    utils::enter_call_site("include_files", &input);
+
     match files::impl_include_files(input) {
         Ok(ts) => ts,
         Err(err) => err.to_compile_error().into(),
     }
 }
+
+/// Signs the plaintext of a file with the ed25519 secret key from the specified
+/// file.
+///
+/// # Example
+///
+/// ```
+/// # use include_crypt_codegen::sign_file;
+/// let (signature, public_key) = sign_file!("src/lib.rs", "../include-crypt/tests/signing.key");
+/// ```
+#[proc_macro]
+pub fn sign_file(input: TokenStream) -> TokenStream {
+    match signature::impl_sign_file(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Signs the manifest of all the files in the specified folder with the ed25519
+/// secret key from the specified file.
+///
+/// # Example
+///
+/// ```
+/// # use include_crypt_codegen::sign_files;
+/// let (signature, public_key) = sign_files!("src", "../include-crypt/tests/signing.key");
+/// ```
+#[proc_macro]
+pub fn sign_files(input: TokenStream) -> TokenStream {
+    match signature::impl_sign_files(input) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error().into(),
+    }
+}
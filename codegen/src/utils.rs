@@ -1,11 +1,187 @@
-use std::{fs::File, io::Read, path::PathBuf};
+use crate::{compression::Compression, escrow::Escrow};
+use include_crypt_crypto::{key::EncryptionKey, mac::hmac_sha256};
+use rand::{rngs::OsRng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use std::{
+    cell::Cell,
+    collections::hash_map::DefaultHasher,
+    fs::File,
+    hash::{Hash, Hasher},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+/// The environment variable which contains the seed for reproducible builds.
+pub(crate) const SEED_VARIABLE: &str = "INCLUDE_CRYPT_SEED";
+
+thread_local! {
+    /// The hash of the macro that is being expanded (see [`enter_call_site`]).
+    static CALL_SITE: Cell<[u8; 32]> = const { Cell::new([0; 32]) };
+}
+
+/// Identifies the macro that is being expanded by the SHA-256 of its name,
+/// e.g. `encrypt_aes`, and its arguments. The name selects the cipher of the
+/// built-in algorithms and the arguments contain the path of custom ciphers,
+/// so that the same file embedded with different ciphers or options gets
+/// independent keys. Identical invocations embed identical ciphertexts. The
+/// `CARGO_MANIFEST_DIR` is removed from absolute paths in the arguments (see
+/// [`relative_path`]).
+///
+/// # Parameters
+///
+/// - `name`: The name of the proc macro.
+/// - `input`: The arguments of the proc macro.
+pub(crate) fn enter_call_site(name: &str, input: &proc_macro::TokenStream) {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let mut input = input.to_string();
+    if !manifest_dir.is_empty() {
+        input = input.replace(&manifest_dir, "");
+    }
+
+    let hash = Sha256::new()
+        .chain_update(name.as_bytes())
+        .chain_update([0])
+        .chain_update(input.as_bytes())
+        .finalize();
+
+    CALL_SITE.with(|call_site| call_site.set(hash.into()));
+}
+
+/// Returns the hash of the macro that is being expanded.
+pub(crate) fn call_site() -> [u8; 32] { CALL_SITE.with(Cell::get) }
+
+/// The generator of the keys, nonces and key shares of a file.
+///
+/// If the `INCLUDE_CRYPT_SEED` environment variable is set, the generator is
+/// seeded with the HMAC-SHA256 of the call site (see [`enter_call_site`]), the
+/// file path and the content of the file, so that the same seed always
+/// produces the same binary. Otherwise the operating system generates the
+/// random bytes.
+pub(crate) enum KeyRng {
+    Os(OsRng),
+
+    /// The seed of the file and the generator which is seeded with it.
+    Seeded([u8; 32], Box<ChaCha20Rng>),
+}
+
+impl KeyRng {
+    /// Creates the generator for the specified file.
+    ///
+    /// # Parameters
+    ///
+    /// - `file_path`: The path of the file as specified in the macro (see
+    ///   [`relative_path`]).
+    /// - `data`: The content of the file.
+    pub(crate) fn new(file_path: &str, data: &[u8]) -> Self {
+        let seed = match std::env::var(SEED_VARIABLE) {
+            Ok(seed) if !seed.is_empty() => seed,
+            _ => return Self::Os(OsRng),
+        };
+
+        let file_path = relative_path(file_path);
+        let context = [&call_site()[..], file_path.as_bytes(), &[0], data].concat();
+        Self::seeded(hmac_sha256(&context, seed.as_bytes()))
+    }
+
+    /// Creates the generator for the seed.
+    fn seeded(seed: [u8; 32]) -> Self { Self::Seeded(seed, Box::new(ChaCha20Rng::from_seed(seed))) }
+
+    /// Generates a key with the specified length.
+    pub(crate) fn key(&mut self, key_len: usize) -> EncryptionKey {
+        let mut key = vec![0u8; key_len];
+        self.fill(&mut key[..]);
+
+        EncryptionKey::from(key)
+    }
+
+    /// Derives a key with the specified length from the seed and the label,
+    /// e.g. `key` or `nonce`. Keys with different labels are independent of
+    /// each other and of the generated bytes, so a nonce never equals the key
+    /// of a build where the key hasn't been specified. The seed of the file
+    /// depends on the call site, so the same file embedded with another cipher
+    /// never reuses the key and nonce. Without a seed, the key is generated
+    /// randomly.
+    pub(crate) fn derive(&mut self, label: &str, key_len: usize) -> EncryptionKey {
+        let seed = match self {
+            Self::Os(_) => return self.key(key_len),
+            Self::Seeded(seed, _) => hmac_sha256(label.as_bytes(), &seed[..]),
+        };
+
+        let mut key = vec![0u8; key_len];
+        ChaCha20Rng::from_seed(seed).fill_bytes(&mut key[..]);
+
+        EncryptionKey::from(key)
+    }
+
+    /// Creates an independent generator, e.g. for the layers of a cascade.
+    pub(crate) fn fork(&mut self) -> Self {
+        match self {
+            Self::Os(_) => Self::Os(OsRng),
+            Self::Seeded(_, rng) => Self::seeded(rng.gen()),
+        }
+    }
+}
+
+impl RngCore for KeyRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Os(rng) => rng.next_u32(),
+            Self::Seeded(_, rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Os(rng) => rng.next_u64(),
+            Self::Seeded(_, rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Os(rng) => rng.fill_bytes(dest),
+            Self::Seeded(_, rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            Self::Os(rng) => rng.try_fill_bytes(dest),
+            Self::Seeded(_, rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+/// Returns the path of the file as specified in the macro. Absolute paths
+/// inside of the `CARGO_MANIFEST_DIR` are made relative, so that the path
+/// doesn't depend on the location of the checkout.
+pub(crate) fn relative_path(file_path: &str) -> String {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+
+    Path::new(file_path)
+        .strip_prefix(&manifest_dir)
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|_| file_path.to_string())
+        .replace("\\", "/")
+}
 
 pub(crate) fn error_mapping<E: std::fmt::Display>(error: E) -> syn::Error {
     syn::Error::new(proc_macro2::Span::mixed_site(), error)
 }
 
-/// Opens the specified file and returns the content.
-pub(crate) fn read_file<P: Into<PathBuf>>(file_path: P) -> syn::Result<Vec<u8>> {
+/// Opens the specified file and returns the content compressed with the
+/// specified algorithm, followed by the id of the algorithm, together with the
+/// escrow of the file.
+pub(crate) fn read_file(file_path: &str, compression: Compression) -> syn::Result<(Vec<u8>, Escrow)> {
+    let content = read_file_raw(file_path)?;
+    let escrow = Escrow::new(file_path, &content)?;
+
+    Ok((compression.compress(content)?, escrow))
+}
+
+/// Opens the specified file and returns the content without modifying it.
+pub(crate) fn read_file_raw<P: Into<PathBuf>>(file_path: P) -> syn::Result<Vec<u8>> {
     let file_path = file_path.into();
 
     // Create the correct path to the file
@@ -30,17 +206,73 @@ pub(crate) fn read_file<P: Into<PathBuf>>(file_path: P) -> syn::Result<Vec<u8>>
     let mut file_bytes = Vec::with_capacity(file_size as usize);
     file.read_to_end(&mut file_bytes).map_err(error_mapping)?;
 
-    // Compress the file if the feature is set
+    Ok(file_bytes)
+}
+
+/// Finds all the files in the specified folder.
+///
+/// # Returns
+///
+/// Returns the absolute path of each file together with the `/` separated
+/// relative path and the hash of the relative path, which is used to look up
+/// the file at runtime.
+pub(crate) fn read_folder(folder_path: &str) -> syn::Result<Vec<(PathBuf, String, String)>> {
+    // Create the correct path to the file
     //
-    #[cfg(feature = "compression")]
-    {
-        use std::io::Write;
+    let folder_path = PathBuf::from(folder_path);
+    let folder_path = if folder_path.is_relative() {
+        PathBuf::from(
+            std::env::var("CARGO_MANIFEST_DIR").expect("Failed to fine 'CARGO_MANIFEST_DIR' environment variable"),
+        )
+        .join(folder_path)
+    } else {
+        folder_path
+    };
 
-        let mut encoder = libflate::deflate::Encoder::new(Vec::with_capacity(file_bytes.len()));
-        encoder.write_all(&file_bytes).map_err(error_mapping)?;
-        encoder.finish().into_result().map_err(error_mapping)
-    }
+    // Check if the folder exists
+    //
+    if !folder_path.exists() {
+        return Err(error_mapping(format!("Folder {:?} could not be found.", folder_path)));
+    };
 
-    #[cfg(not(feature = "compression"))]
-    Ok(file_bytes)
+    // Find the paths of all the files in the folder
+    //
+    let file_paths = glob::glob(format!("{}/**/*", folder_path.display()).as_str())
+        .map_err(error_mapping)?
+        .filter_map(Result::ok)
+        .filter(|path| {
+            std::fs::metadata(path)
+                .map(|metadata| metadata.is_file())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    // Sort the files by their relative path, so that the order doesn't depend on
+    // the file system.
+    //
+    let mut file_paths = file_paths
+        .into_iter()
+        .map(|file_path| {
+            let path = file_path
+                .strip_prefix(&folder_path)
+                .map(|path| path.display().to_string())
+                .unwrap_or_default()
+                .replace("\\", "/");
+
+            (file_path, path)
+        })
+        .collect::<Vec<_>>();
+    file_paths.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+    // Convert the relative paths into hashes
+    //
+    Ok(file_paths
+        .into_iter()
+        .map(|(file_path, path)| {
+            let mut hasher = DefaultHasher::new();
+            path.hash(&mut hasher);
+
+            (file_path, path, hasher.finish().to_string())
+        })
+        .collect())
 }
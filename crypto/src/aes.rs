@@ -1,4 +1,7 @@
-use crate::key::EncryptionKey;
+use crate::{
+    cipher::{Cipher, CipherError},
+    key::EncryptionKey,
+};
 use aes::{
     cipher::{NewStreamCipher, StreamCipher},
     Aes256,
@@ -47,6 +50,27 @@ pub fn aes_decrypt<K: AsRef<EncryptionKey>>(data: &mut [u8], key: K, nonce: K) -
     Cfb::<Aes256>::new_var(key.as_ref(), nonce.as_ref()).map(|mut aes| aes.decrypt(data))
 }
 
+/// The aes cfb cipher which can be used through the [`Cipher`] trait.
+pub struct Aes;
+
+impl Cipher for Aes {
+    fn key_len(&self) -> usize { AES_KEY_LEN }
+
+    fn nonce_len(&self) -> usize { AES_NONCE_LEN }
+
+    fn encrypt(&self, data: &mut [u8], key: &EncryptionKey, nonce: &EncryptionKey) -> Result<Vec<u8>, CipherError> {
+        aes_encrypt(data, key, nonce)
+            .map(|_| Vec::new())
+            .map_err(|_| CipherError::InvalidLength)
+    }
+
+    fn decrypt(
+        &self, data: &mut [u8], key: &EncryptionKey, nonce: &EncryptionKey, _: &[u8],
+    ) -> Result<(), CipherError> {
+        aes_decrypt(data, key, nonce).map_err(|_| CipherError::InvalidLength)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -58,8 +82,8 @@ mod tests {
         let key = EncryptionKey::random(AES_KEY_LEN);
         let nonce = EncryptionKey::random(AES_NONCE_LEN);
 
-        assert_eq!(aes_encrypt(data.as_mut_slice(), &key, &nonce).is_ok(), true);
-        assert_eq!(aes_decrypt(data.as_mut_slice(), &key, &nonce).is_ok(), true);
+        assert!(aes_encrypt(data.as_mut_slice(), &key, &nonce).is_ok());
+        assert!(aes_decrypt(data.as_mut_slice(), &key, &nonce).is_ok());
 
         assert_eq!(data, "The quick brown fox jumps over the lazy dog.".as_bytes());
     }
@@ -0,0 +1,141 @@
+use crate::{
+    cipher::{Cipher, CipherError},
+    key::EncryptionKey,
+};
+use aes_gcm::{
+    aead::{AeadInPlace, KeyInit, Nonce, Tag},
+    Aes256Gcm, Error,
+};
+
+/// Default key length (AES-256-GCM)
+pub const AES_GCM_KEY_LEN: usize = 32;
+
+/// Nonce length (AES-256-GCM)
+pub const AES_GCM_NONCE_LEN: usize = 12;
+
+/// Authentication tag length (AES-256-GCM)
+pub const AES_GCM_TAG_LEN: usize = 16;
+
+/// Creates the cipher and nonce from the specified key and nonce.
+#[inline(always)]
+fn aes_gcm_cipher<'a>(
+    key: &EncryptionKey, nonce: &'a EncryptionKey,
+) -> Result<(Aes256Gcm, &'a Nonce<Aes256Gcm>), Error> {
+    if nonce.len() != AES_GCM_NONCE_LEN {
+        return Err(Error);
+    }
+
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| Error)?;
+    Ok((cipher, Nonce::<Aes256Gcm>::from_slice(nonce)))
+}
+
+/// Encrypts the specified data with the AES GCM cipher.
+///
+/// # Parameters
+///
+/// - `data`: The plaintext data buffer. After this function has been called, it
+///   will store the encrypted data.
+/// - `key`: The encryption key. It must be exactly 32 bytes.
+/// - `nonce`: The unique nonce. It must be exactly 12 bytes.
+///
+/// # Returns
+///
+/// If the data could be successfully encrypted, the authentication tag will be
+/// returned. If the key or nonce are invalid, `Err(Error)` will be returned.
+#[inline(always)]
+pub fn aes_gcm_encrypt<K: AsRef<EncryptionKey>>(
+    data: &mut [u8], key: K, nonce: K,
+) -> Result<[u8; AES_GCM_TAG_LEN], Error> {
+    let (cipher, nonce) = aes_gcm_cipher(key.as_ref(), nonce.as_ref())?;
+
+    cipher.encrypt_in_place_detached(nonce, &[], data).map(|tag| tag.into())
+}
+
+/// Decrypts the specified data with the AES GCM cipher and verifies the
+/// authentication tag.
+///
+/// # Parameters
+///
+/// - `data`: The encrypted data buffer. After this function has been called, it
+///   will store the decrypted data. If the tag doesn't match, the buffer will
+///   not be modified.
+/// - `key`: The decryption key. It must be exactly 32 bytes.
+/// - `nonce`: The unique nonce. It must be exactly 12 bytes.
+/// - `tag`: The authentication tag that has been returned by
+///   [`aes_gcm_encrypt`].
+///
+/// # Returns
+///
+/// If the data could be successfully decrypted `Ok(())` will be returned. If
+/// the key or nonce are invalid or the data has been tampered with,
+/// `Err(Error)` will be returned.
+#[inline(always)]
+pub fn aes_gcm_decrypt<K: AsRef<EncryptionKey>>(data: &mut [u8], key: K, nonce: K, tag: &[u8]) -> Result<(), Error> {
+    if tag.len() != AES_GCM_TAG_LEN {
+        return Err(Error);
+    }
+
+    let (cipher, nonce) = aes_gcm_cipher(key.as_ref(), nonce.as_ref())?;
+    cipher.decrypt_in_place_detached(nonce, &[], data, Tag::<Aes256Gcm>::from_slice(tag))
+}
+
+/// The aes gcm cipher which can be used through the [`Cipher`] trait.
+pub struct AesGcm;
+
+impl Cipher for AesGcm {
+    fn key_len(&self) -> usize { AES_GCM_KEY_LEN }
+
+    fn nonce_len(&self) -> usize { AES_GCM_NONCE_LEN }
+
+    fn tag_len(&self) -> usize { AES_GCM_TAG_LEN }
+
+    fn encrypt(&self, data: &mut [u8], key: &EncryptionKey, nonce: &EncryptionKey) -> Result<Vec<u8>, CipherError> {
+        if key.len() != AES_GCM_KEY_LEN || nonce.len() != AES_GCM_NONCE_LEN {
+            return Err(CipherError::InvalidLength);
+        }
+
+        aes_gcm_encrypt(data, key, nonce)
+            .map(|tag| tag.to_vec())
+            .map_err(|_| CipherError::InvalidLength)
+    }
+
+    fn decrypt(
+        &self, data: &mut [u8], key: &EncryptionKey, nonce: &EncryptionKey, tag: &[u8],
+    ) -> Result<(), CipherError> {
+        if key.len() != AES_GCM_KEY_LEN || nonce.len() != AES_GCM_NONCE_LEN || tag.len() != AES_GCM_TAG_LEN {
+            return Err(CipherError::InvalidLength);
+        }
+
+        aes_gcm_decrypt(data, key, nonce, tag).map_err(|_| CipherError::Integrity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::EncryptionKey;
+
+    #[test]
+    fn test_aes_gcm() {
+        let mut data = Vec::from("The quick brown fox jumps over the lazy dog.".as_bytes());
+        let key = EncryptionKey::random(AES_GCM_KEY_LEN);
+        let nonce = EncryptionKey::random(AES_GCM_NONCE_LEN);
+
+        let tag = aes_gcm_encrypt(data.as_mut_slice(), &key, &nonce).unwrap();
+        assert!(aes_gcm_decrypt(data.as_mut_slice(), &key, &nonce, &tag).is_ok());
+
+        assert_eq!(data, "The quick brown fox jumps over the lazy dog.".as_bytes());
+    }
+
+    #[test]
+    fn test_aes_gcm_tampered() {
+        let mut data = Vec::from("The quick brown fox jumps over the lazy dog.".as_bytes());
+        let key = EncryptionKey::random(AES_GCM_KEY_LEN);
+        let nonce = EncryptionKey::random(AES_GCM_NONCE_LEN);
+
+        let tag = aes_gcm_encrypt(data.as_mut_slice(), &key, &nonce).unwrap();
+        data[0] ^= 1;
+
+        assert!(aes_gcm_decrypt(data.as_mut_slice(), &key, &nonce, &tag).is_err());
+    }
+}
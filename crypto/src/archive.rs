@@ -0,0 +1,95 @@
+use std::{convert::TryFrom, ops::Range};
+
+/// Concatenates multiple files into one solid archive, so that they can be
+/// compressed and encrypted at once. The archive starts with the index, which
+/// contains the number of files followed by the id and the length of each
+/// file. The contents of the files follow the index in the same order.
+///
+/// # Parameters
+///
+/// - `entries`: The ids and contents of the files.
+pub fn solid_archive<'a, I: IntoIterator<Item = (&'a str, &'a [u8])>>(entries: I) -> Vec<u8> {
+    let entries = entries.into_iter().collect::<Vec<_>>();
+
+    let mut archive = Vec::new();
+    archive.extend_from_slice(&(entries.len() as u64).to_le_bytes());
+    for (id, data) in &entries {
+        archive.extend_from_slice(&(id.len() as u64).to_le_bytes());
+        archive.extend_from_slice(id.as_bytes());
+        archive.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    }
+    for (_, data) in &entries {
+        archive.extend_from_slice(data);
+    }
+
+    archive
+}
+
+/// Reads the index of a solid archive (see [`solid_archive`]).
+///
+/// # Parameters
+///
+/// - `archive`: The decrypted and decompressed archive.
+///
+/// # Returns
+///
+/// Returns the id of every file and the range of its content in the archive.
+/// If the index is truncated or a file exceeds the archive, `None` will be
+/// returned.
+pub fn solid_index(archive: &[u8]) -> Option<Vec<(String, Range<usize>)>> {
+    let mut position = 0;
+    let count = read_len(archive, &mut position)?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let id_len = read_len(archive, &mut position)?;
+        let id = String::from_utf8(read(archive, &mut position, id_len)?.to_vec()).ok()?;
+        entries.push((id, read_len(archive, &mut position)?));
+    }
+
+    let mut offset = position;
+    let mut index = Vec::with_capacity(entries.len());
+    for (id, len) in entries {
+        let end = offset.checked_add(len).filter(|end| *end <= archive.len())?;
+        index.push((id, offset..end));
+        offset = end;
+    }
+
+    Some(index)
+}
+
+/// Reads the bytes at the position and advances it.
+fn read<'a>(archive: &'a [u8], position: &mut usize, len: usize) -> Option<&'a [u8]> {
+    let bytes = archive.get(*position..position.checked_add(len)?)?;
+    *position += len;
+
+    Some(bytes)
+}
+
+/// Reads a little endian length at the position and advances it.
+fn read_len(archive: &[u8], position: &mut usize) -> Option<usize> {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(read(archive, position, 8)?);
+
+    usize::try_from(u64::from_le_bytes(bytes)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solid_archive() {
+        let archive = solid_archive(vec![("a", &b"first"[..]), ("b", &b""[..]), ("c", &b"third"[..])]);
+        let index = solid_index(&archive).unwrap();
+
+        let files = index
+            .iter()
+            .map(|(id, range)| (id.as_str(), &archive[range.clone()]))
+            .collect::<Vec<_>>();
+        assert_eq!(files, vec![("a", &b"first"[..]), ("b", &b""[..]), ("c", &b"third"[..])]);
+
+        assert_eq!(solid_index(&archive[..archive.len() - 1]), None);
+        assert_eq!(solid_index(&archive[..4]), None);
+        assert_eq!(solid_index(&solid_archive(Vec::new())), Some(Vec::new()));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{
+    cipher::{Cipher, CipherError},
+    key::EncryptionKey,
+};
+use chacha20poly1305::{
+    aead::{AeadInPlace, KeyInit, Nonce, Tag},
+    Error, XChaCha20Poly1305,
+};
+
+/// Default key length (XChaCha20-Poly1305)
+pub const CHACHA_KEY_LEN: usize = 32;
+
+/// Nonce length (XChaCha20-Poly1305)
+pub const CHACHA_NONCE_LEN: usize = 24;
+
+/// Authentication tag length (XChaCha20-Poly1305)
+pub const CHACHA_TAG_LEN: usize = 16;
+
+/// Creates the cipher and nonce from the specified key and nonce.
+#[inline(always)]
+fn chacha_cipher<'a>(
+    key: &EncryptionKey, nonce: &'a EncryptionKey,
+) -> Result<(XChaCha20Poly1305, &'a Nonce<XChaCha20Poly1305>), Error> {
+    if nonce.len() != CHACHA_NONCE_LEN {
+        return Err(Error);
+    }
+
+    let cipher = XChaCha20Poly1305::new_from_slice(key).map_err(|_| Error)?;
+    Ok((cipher, Nonce::<XChaCha20Poly1305>::from_slice(nonce)))
+}
+
+/// Encrypts the specified data with the XChaCha20-Poly1305 cipher.
+///
+/// # Parameters
+///
+/// - `data`: The plaintext data buffer. After this function has been called, it
+///   will store the encrypted data.
+/// - `key`: The encryption key. It must be exactly 32 bytes.
+/// - `nonce`: The unique nonce. It must be exactly 24 bytes.
+///
+/// # Returns
+///
+/// If the data could be successfully encrypted, the authentication tag will be
+/// returned. If the key or nonce are invalid, `Err(Error)` will be returned.
+#[inline(always)]
+pub fn chacha_encrypt<K: AsRef<EncryptionKey>>(
+    data: &mut [u8], key: K, nonce: K,
+) -> Result<[u8; CHACHA_TAG_LEN], Error> {
+    let (cipher, nonce) = chacha_cipher(key.as_ref(), nonce.as_ref())?;
+
+    cipher.encrypt_in_place_detached(nonce, &[], data).map(|tag| tag.into())
+}
+
+/// Decrypts the specified data with the XChaCha20-Poly1305 cipher and verifies
+/// the authentication tag.
+///
+/// # Parameters
+///
+/// - `data`: The encrypted data buffer. After this function has been called, it
+///   will store the decrypted data. If the tag doesn't match, the buffer will
+///   not be modified.
+/// - `key`: The decryption key. It must be exactly 32 bytes.
+/// - `nonce`: The unique nonce. It must be exactly 24 bytes.
+/// - `tag`: The authentication tag that has been returned by
+///   [`chacha_encrypt`].
+///
+/// # Returns
+///
+/// If the data could be successfully decrypted `Ok(())` will be returned. If
+/// the key or nonce are invalid or the data has been tampered with,
+/// `Err(Error)` will be returned.
+#[inline(always)]
+pub fn chacha_decrypt<K: AsRef<EncryptionKey>>(data: &mut [u8], key: K, nonce: K, tag: &[u8]) -> Result<(), Error> {
+    if tag.len() != CHACHA_TAG_LEN {
+        return Err(Error);
+    }
+
+    let (cipher, nonce) = chacha_cipher(key.as_ref(), nonce.as_ref())?;
+    cipher.decrypt_in_place_detached(nonce, &[], data, Tag::<XChaCha20Poly1305>::from_slice(tag))
+}
+
+/// The xchacha20-poly1305 cipher which can be used through the [`Cipher`]
+/// trait.
+pub struct ChaCha;
+
+impl Cipher for ChaCha {
+    fn key_len(&self) -> usize { CHACHA_KEY_LEN }
+
+    fn nonce_len(&self) -> usize { CHACHA_NONCE_LEN }
+
+    fn tag_len(&self) -> usize { CHACHA_TAG_LEN }
+
+    fn encrypt(&self, data: &mut [u8], key: &EncryptionKey, nonce: &EncryptionKey) -> Result<Vec<u8>, CipherError> {
+        if key.len() != CHACHA_KEY_LEN || nonce.len() != CHACHA_NONCE_LEN {
+            return Err(CipherError::InvalidLength);
+        }
+
+        chacha_encrypt(data, key, nonce)
+            .map(|tag| tag.to_vec())
+            .map_err(|_| CipherError::InvalidLength)
+    }
+
+    fn decrypt(
+        &self, data: &mut [u8], key: &EncryptionKey, nonce: &EncryptionKey, tag: &[u8],
+    ) -> Result<(), CipherError> {
+        if key.len() != CHACHA_KEY_LEN || nonce.len() != CHACHA_NONCE_LEN || tag.len() != CHACHA_TAG_LEN {
+            return Err(CipherError::InvalidLength);
+        }
+
+        chacha_decrypt(data, key, nonce, tag).map_err(|_| CipherError::Integrity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::EncryptionKey;
+
+    #[test]
+    fn test_chacha() {
+        let mut data = Vec::from("The quick brown fox jumps over the lazy dog.".as_bytes());
+        let key = EncryptionKey::random(CHACHA_KEY_LEN);
+        let nonce = EncryptionKey::random(CHACHA_NONCE_LEN);
+
+        let tag = chacha_encrypt(data.as_mut_slice(), &key, &nonce).unwrap();
+        assert!(chacha_decrypt(data.as_mut_slice(), &key, &nonce, &tag).is_ok());
+
+        assert_eq!(data, "The quick brown fox jumps over the lazy dog.".as_bytes());
+    }
+
+    #[test]
+    fn test_chacha_tampered() {
+        let mut data = Vec::from("The quick brown fox jumps over the lazy dog.".as_bytes());
+        let key = EncryptionKey::random(CHACHA_KEY_LEN);
+        let nonce = EncryptionKey::random(CHACHA_NONCE_LEN);
+
+        let tag = chacha_encrypt(data.as_mut_slice(), &key, &nonce).unwrap();
+        data[0] ^= 1;
+
+        assert!(chacha_decrypt(data.as_mut_slice(), &key, &nonce, &tag).is_err());
+    }
+}
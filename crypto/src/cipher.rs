@@ -0,0 +1,203 @@
+use crate::key::EncryptionKey;
+use std::{convert::TryInto, fmt};
+
+/// The magic bytes at the start of a sealed buffer.
+const SEALED_MAGIC: &[u8; 4] = b"ICS1";
+
+/// The errors that can be returned by a [`Cipher`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CipherError {
+    /// The key, nonce or tag doesn't have the length the cipher expects.
+    InvalidLength,
+
+    /// The authentication tag didn't match the data.
+    Integrity,
+}
+
+impl fmt::Display for CipherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CipherError::InvalidLength => write!(f, "invalid key, nonce or tag length"),
+            CipherError::Integrity => write!(f, "the authentication tag didn't match"),
+        }
+    }
+}
+
+impl std::error::Error for CipherError {}
+
+/// A symmetric cipher that can be used to encrypt the embedded files.
+///
+/// All the built-in algorithms implement this trait. Custom ciphers can
+/// implement it as well and then be used with the `include_crypt!` and
+/// `include_dir!` macros by passing the path to the type. Because the proc
+/// macros can't execute code of the crate they are used in, the files have to
+/// be sealed with [`seal`] ahead of time (e.g. in a build script).
+pub trait Cipher: Sync {
+    /// The length of the key in bytes.
+    fn key_len(&self) -> usize;
+
+    /// The length of the nonce in bytes. Ciphers that don't use a nonce
+    /// return `0`.
+    fn nonce_len(&self) -> usize { 0 }
+
+    /// The length of the authentication tag in bytes. Ciphers that don't
+    /// authenticate the data return `0`.
+    fn tag_len(&self) -> usize { 0 }
+
+    /// Encrypts the specified data.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: The plaintext data buffer. After this function has been
+    ///   called, it will store the encrypted data.
+    /// - `key`: The encryption key with a length of [`Cipher::key_len`].
+    /// - `nonce`: The nonce with a length of [`Cipher::nonce_len`].
+    ///
+    /// # Returns
+    ///
+    /// Returns the authentication tag, which is empty for unauthenticated
+    /// ciphers.
+    fn encrypt(&self, data: &mut [u8], key: &EncryptionKey, nonce: &EncryptionKey) -> Result<Vec<u8>, CipherError>;
+
+    /// Decrypts the specified data.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: The encrypted data buffer. After this function has been
+    ///   called, it will store the decrypted data.
+    /// - `key`: The decryption key with a length of [`Cipher::key_len`].
+    /// - `nonce`: The nonce with a length of [`Cipher::nonce_len`].
+    /// - `tag`: The authentication tag that has been returned by
+    ///   [`Cipher::encrypt`].
+    fn decrypt(
+        &self, data: &mut [u8], key: &EncryptionKey, nonce: &EncryptionKey, tag: &[u8],
+    ) -> Result<(), CipherError>;
+}
+
+/// A buffer that has been encrypted with [`seal`] together with the key
+/// material which is needed to decrypt it.
+///
+/// The key is stored in plaintext, so anyone who has the sealed file can
+/// decrypt it. Sealed files have to be treated like the original files until
+/// they are embedded.
+pub struct Sealed {
+    /// The encryption key.
+    pub key: EncryptionKey,
+
+    /// The nonce which has been used.
+    pub nonce: EncryptionKey,
+
+    /// The authentication tag.
+    pub tag: Vec<u8>,
+
+    /// The encrypted data.
+    pub data: Vec<u8>,
+}
+
+impl Sealed {
+    /// Parses a buffer that has been created by [`seal`].
+    ///
+    /// # Returns
+    ///
+    /// If the buffer isn't a valid sealed buffer, `None` will be returned.
+    pub fn parse(buffer: &[u8]) -> Option<Self> {
+        fn field<'a>(buffer: &mut &'a [u8]) -> Option<&'a [u8]> {
+            let len = u16::from_le_bytes(buffer.get(..2)?.try_into().ok()?) as usize;
+            let field = buffer.get(2..2 + len)?;
+            *buffer = &buffer[2 + len..];
+
+            Some(field)
+        }
+
+        let mut buffer = buffer.strip_prefix(&SEALED_MAGIC[..])?;
+        let key = field(&mut buffer)?.to_vec();
+        let nonce = field(&mut buffer)?.to_vec();
+        let tag = field(&mut buffer)?.to_vec();
+
+        Some(Self {
+            key: key.into(),
+            nonce: nonce.into(),
+            tag,
+            data: buffer.to_vec(),
+        })
+    }
+}
+
+/// Encrypts the data with the cipher and a random nonce and stores the key
+/// material next to it, so that it can be embedded with a custom cipher.
+///
+/// **The key is stored in plaintext next to the ciphertext.** The sealed file
+/// only hides the content once it has been embedded, where the key is
+/// obfuscated. Don't ship or commit sealed files which wouldn't be safe to
+/// ship or commit unencrypted, e.g. write them to `OUT_DIR` in a build script.
+///
+/// # Parameters
+///
+/// - `cipher`: The cipher that should be used.
+/// - `data`: The plaintext data.
+/// - `key`: The encryption key with a length of [`Cipher::key_len`].
+///
+/// # Returns
+///
+/// Returns the sealed buffer which can be written to a file and passed to
+/// `include_crypt!`. If the cipher fails to encrypt the data, the error will
+/// be returned.
+///
+/// # Example
+///
+/// ```ignore
+/// // build.rs
+/// let key = EncryptionKey::random(MyCipher.key_len());
+/// let sealed = seal(&MyCipher, &std::fs::read("assets/file.txt")?, &key)?;
+/// std::fs::write(out_dir.join("file.txt"), sealed)?;
+/// ```
+pub fn seal(cipher: &dyn Cipher, data: &[u8], key: &EncryptionKey) -> Result<Vec<u8>, CipherError> {
+    let nonce = EncryptionKey::random(cipher.nonce_len());
+
+    let mut data = data.to_vec();
+    let tag = cipher.encrypt(data.as_mut_slice(), key, &nonce)?;
+
+    let mut sealed = SEALED_MAGIC.to_vec();
+    for field in [&key[..], &nonce[..], &tag[..]].iter() {
+        let len: u16 = field.len().try_into().map_err(|_| CipherError::InvalidLength)?;
+
+        sealed.extend_from_slice(&len.to_le_bytes());
+        sealed.extend_from_slice(field);
+    }
+    sealed.extend_from_slice(&data);
+
+    Ok(sealed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{aes_gcm::AesGcm, xor::Xor};
+
+    #[test]
+    fn test_seal() {
+        let data = "The quick brown fox jumps over the lazy dog.".as_bytes();
+        let key = EncryptionKey::random(AesGcm.key_len());
+
+        let sealed = Sealed::parse(&seal(&AesGcm, data, &key).unwrap()).unwrap();
+        assert_eq!(*sealed.key, *key);
+        assert_eq!(sealed.nonce.len(), AesGcm.nonce_len());
+        assert_eq!(sealed.tag.len(), AesGcm.tag_len());
+
+        let mut buffer = sealed.data;
+        assert!(AesGcm
+            .decrypt(buffer.as_mut_slice(), &sealed.key, &sealed.nonce, &sealed.tag)
+            .is_ok());
+        assert_eq!(buffer, data);
+    }
+
+    #[test]
+    fn test_seal_invalid() {
+        assert!(Sealed::parse(b"").is_none());
+        assert!(Sealed::parse(b"ICS1\x20\x00").is_none());
+
+        let key = EncryptionKey::random(Xor.key_len());
+        let sealed = seal(&Xor, b"Hello World", &key).unwrap();
+        assert!(Sealed::parse(&sealed[1..]).is_none());
+    }
+}
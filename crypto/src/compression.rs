@@ -0,0 +1,99 @@
+use std::{fmt, str::FromStr};
+
+/// The compression algorithms. The id of the algorithm is stored behind
+/// the compressed content, so that every file is decompressed with the
+/// algorithm it has been compressed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// The content is stored as it is.
+    None,
+    Deflate,
+    Zstd,
+    Lz4,
+    Brotli,
+
+    /// Zstd with the dictionary that has been trained on all the files of a
+    /// folder.
+    ZstdDictionary,
+}
+
+impl Algorithm {
+    /// All the algorithms in the order of their ids.
+    pub const ALL: [Algorithm; 6] = [
+        Algorithm::None,
+        Algorithm::Deflate,
+        Algorithm::Zstd,
+        Algorithm::Lz4,
+        Algorithm::Brotli,
+        Algorithm::ZstdDictionary,
+    ];
+
+    /// Returns the id which is stored behind the compressed content.
+    pub fn id(self) -> u8 {
+        match self {
+            Algorithm::None => 0,
+            Algorithm::Deflate => 1,
+            Algorithm::Zstd => 2,
+            Algorithm::Lz4 => 3,
+            Algorithm::Brotli => 4,
+            Algorithm::ZstdDictionary => 5,
+        }
+    }
+
+    /// Returns the algorithm of the id or `None` if the id is unknown.
+    pub fn from_id(id: u8) -> Option<Self> { Self::ALL.iter().copied().find(|algorithm| algorithm.id() == id) }
+
+    /// Returns the range of the supported compression levels and the default
+    /// level. Algorithms without levels return `None`.
+    pub fn levels(self) -> Option<(std::ops::RangeInclusive<i32>, i32)> {
+        match self {
+            Algorithm::None | Algorithm::Lz4 => None,
+            Algorithm::Deflate => Some((0..=10, 6)),
+            Algorithm::Zstd | Algorithm::ZstdDictionary => Some((1..=22, 3)),
+            Algorithm::Brotli => Some((0..=11, 11)),
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Algorithm::None),
+            "deflate" => Ok(Algorithm::Deflate),
+            "zstd" => Ok(Algorithm::Zstd),
+            "lz4" => Ok(Algorithm::Lz4),
+            "brotli" => Ok(Algorithm::Brotli),
+            "zstd_dict" => Ok(Algorithm::ZstdDictionary),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Algorithm::None => write!(f, "none"),
+            Algorithm::Deflate => write!(f, "deflate"),
+            Algorithm::Zstd => write!(f, "zstd"),
+            Algorithm::Lz4 => write!(f, "lz4"),
+            Algorithm::Brotli => write!(f, "brotli"),
+            Algorithm::ZstdDictionary => write!(f, "zstd_dict"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_algorithm_ids() {
+        for algorithm in Algorithm::ALL.iter() {
+            assert_eq!(Algorithm::from_id(algorithm.id()), Some(*algorithm));
+            assert_eq!(algorithm.to_string().parse::<Algorithm>(), Ok(*algorithm));
+        }
+        assert_eq!(Algorithm::from_id(0xff), None);
+    }
+}
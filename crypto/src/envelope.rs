@@ -0,0 +1,146 @@
+use crate::{
+    aes_gcm::{AesGcm, AES_GCM_KEY_LEN, AES_GCM_NONCE_LEN, AES_GCM_TAG_LEN},
+    cipher::{Cipher, CipherError},
+    key::EncryptionKey,
+};
+use zeroize::Zeroize;
+
+/// Key length of the master key (AES-256-GCM)
+pub const MASTER_KEY_LEN: usize = AES_GCM_KEY_LEN;
+
+/// The length of the nonce and authentication tag which are stored in front of
+/// the wrapped data key.
+pub const WRAP_OVERHEAD: usize = AES_GCM_NONCE_LEN + AES_GCM_TAG_LEN;
+
+/// Wraps (encrypts) the data key with the master key.
+///
+/// # Parameters
+///
+/// - `data_key`: The data key which encrypts the content.
+/// - `master_key`: The master key. It must be exactly 32 bytes.
+/// - `nonce`: The unique nonce. It must be exactly 12 bytes.
+///
+/// # Returns
+///
+/// Returns the nonce, the authentication tag and the wrapped data key. If the
+/// master key or nonce are invalid, `Err(CipherError::InvalidLength)` will be
+/// returned.
+pub fn wrap_key(
+    data_key: &EncryptionKey, master_key: &EncryptionKey, nonce: &EncryptionKey,
+) -> Result<Vec<u8>, CipherError> {
+    let mut key = data_key.to_vec();
+    let tag = match AesGcm.encrypt(&mut key, master_key, nonce) {
+        Ok(tag) => tag,
+        Err(e) => {
+            key.zeroize();
+            return Err(e);
+        }
+    };
+
+    Ok([&nonce[..], &tag[..], &key[..]].concat())
+}
+
+/// Unwraps (decrypts) the data key that has been wrapped with [`wrap_key`].
+///
+/// # Parameters
+///
+/// - `wrapped`: The nonce, the authentication tag and the wrapped data key.
+/// - `master_key`: The master key. It must be exactly 32 bytes.
+///
+/// # Returns
+///
+/// If the master key is wrong or the wrapped key has been tampered with,
+/// `Err(CipherError::Integrity)` will be returned. Otherwise the data key will
+/// be returned.
+pub fn unwrap_key(wrapped: &[u8], master_key: &EncryptionKey) -> Result<EncryptionKey, CipherError> {
+    if wrapped.len() < WRAP_OVERHEAD {
+        return Err(CipherError::InvalidLength);
+    }
+
+    let (nonce, rest) = wrapped.split_at(AES_GCM_NONCE_LEN);
+    let (tag, key) = rest.split_at(AES_GCM_TAG_LEN);
+
+    // The buffer is only modified if the authentication tag matches, so the
+    // plaintext data key never has to be wiped on failure.
+    //
+    let mut key = key.to_vec();
+    AesGcm.decrypt(&mut key, master_key, &EncryptionKey::from(nonce.to_vec()), tag)?;
+
+    Ok(EncryptionKey::from(key))
+}
+
+/// Rewraps the data key with a new master key, e.g. when the master key is
+/// rotated. The content which has been encrypted with the data key doesn't
+/// change.
+///
+/// # Parameters
+///
+/// - `wrapped`: The data key that has been wrapped with the old master key.
+/// - `old_master_key`: The old master key.
+/// - `new_master_key`: The new master key.
+/// - `nonce`: The unique nonce for the new wrapped key.
+///
+/// # Returns
+///
+/// See [`unwrap_key`] and [`wrap_key`].
+pub fn rewrap_key(
+    wrapped: &[u8], old_master_key: &EncryptionKey, new_master_key: &EncryptionKey, nonce: &EncryptionKey,
+) -> Result<Vec<u8>, CipherError> {
+    wrap_key(&unwrap_key(wrapped, old_master_key)?, new_master_key, nonce)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_key() {
+        let data_key = EncryptionKey::random(32);
+        let master_key = EncryptionKey::random(MASTER_KEY_LEN);
+
+        let wrapped = wrap_key(&data_key, &master_key, &EncryptionKey::random(AES_GCM_NONCE_LEN)).unwrap();
+        assert_eq!(wrapped.len(), WRAP_OVERHEAD + data_key.len());
+        assert_ne!(&wrapped[WRAP_OVERHEAD..], &data_key[..]);
+
+        assert_eq!(*unwrap_key(&wrapped, &master_key).unwrap(), *data_key);
+    }
+
+    #[test]
+    fn test_wrong_master_key() {
+        let data_key = EncryptionKey::random(32);
+        let wrapped = wrap_key(
+            &data_key,
+            &EncryptionKey::random(MASTER_KEY_LEN),
+            &EncryptionKey::random(AES_GCM_NONCE_LEN),
+        )
+        .unwrap();
+
+        assert_eq!(
+            unwrap_key(&wrapped, &EncryptionKey::random(MASTER_KEY_LEN)).err(),
+            Some(CipherError::Integrity)
+        );
+        assert_eq!(
+            unwrap_key(&wrapped[..WRAP_OVERHEAD - 1], &EncryptionKey::random(MASTER_KEY_LEN)).err(),
+            Some(CipherError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn test_rewrap_key() {
+        let data_key = EncryptionKey::random(32);
+        let old_master_key = EncryptionKey::random(MASTER_KEY_LEN);
+        let new_master_key = EncryptionKey::random(MASTER_KEY_LEN);
+
+        let wrapped = wrap_key(&data_key, &old_master_key, &EncryptionKey::random(AES_GCM_NONCE_LEN)).unwrap();
+        let rewrapped = rewrap_key(
+            &wrapped,
+            &old_master_key,
+            &new_master_key,
+            &EncryptionKey::random(AES_GCM_NONCE_LEN),
+        )
+        .unwrap();
+
+        assert!(unwrap_key(&rewrapped, &old_master_key).is_err());
+        assert_eq!(*unwrap_key(&rewrapped, &new_master_key).unwrap(), *data_key);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::key::{EncryptionKey, KeyError};
+use argon2::{Algorithm, Argon2, Params, Version};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+/// Salt length of the password derived keys.
+pub const KDF_SALT_LEN: usize = 16;
+
+/// Key length of the folder key from which the keys of the files are derived.
+pub const FOLDER_KEY_LEN: usize = 32;
+
+/// The context of the derived file keys, so that they never collide with other
+/// keys that are derived with HKDF.
+const FILE_KEY_INFO: &[u8] = b"include-crypt file key";
+
+/// The key derivation function which is used to derive a key from a password.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kdf {
+    /// Argon2id with the memory cost in KiB, the number of iterations and the
+    /// degree of parallelism.
+    Argon2 { m_cost: u32, t_cost: u32, p_cost: u32 },
+
+    /// PBKDF2-HMAC-SHA256 with the number of rounds.
+    Pbkdf2 { rounds: u32 },
+}
+
+impl Kdf {
+    /// Argon2id with the recommended parameters.
+    pub const ARGON2: Kdf = Kdf::Argon2 {
+        m_cost: Params::DEFAULT_M_COST,
+        t_cost: Params::DEFAULT_T_COST,
+        p_cost: Params::DEFAULT_P_COST,
+    };
+    /// PBKDF2-HMAC-SHA256 with the recommended number of rounds.
+    pub const PBKDF2: Kdf = Kdf::Pbkdf2 { rounds: 600_000 };
+
+    /// Derives a key from the password.
+    ///
+    /// # Parameters
+    ///
+    /// - `password`: The password of the user.
+    /// - `salt`: The random salt. It must be at least 8 bytes.
+    /// - `key_len`: The length of the derived key in bytes.
+    ///
+    /// # Returns
+    ///
+    /// Returns the derived key. If the parameters are invalid,
+    /// `Err(KeyError::Derivation)` will be returned.
+    pub fn derive(&self, password: &[u8], salt: &[u8], key_len: usize) -> Result<EncryptionKey, KeyError> {
+        let mut key = vec![0u8; key_len];
+
+        match *self {
+            Kdf::Argon2 { m_cost, t_cost, p_cost } => {
+                let params = Params::new(m_cost, t_cost, p_cost, Some(key_len)).map_err(|_| KeyError::Derivation)?;
+
+                // Argon2 might have written to the key before it failed.
+                //
+                if Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(password, salt, &mut key)
+                    .is_err()
+                {
+                    key.zeroize();
+                    return Err(KeyError::Derivation);
+                }
+            }
+            Kdf::Pbkdf2 { rounds } => {
+                if rounds == 0 || salt.len() < 8 {
+                    return Err(KeyError::Derivation);
+                }
+
+                pbkdf2::pbkdf2_hmac::<Sha256>(password, salt, rounds, &mut key);
+            }
+        }
+
+        Ok(EncryptionKey::from(key))
+    }
+}
+
+impl Default for Kdf {
+    fn default() -> Self { Self::ARGON2 }
+}
+
+/// Derives the key and nonce of a file from the folder key and the path of the
+/// file with HKDF-SHA256.
+///
+/// # Parameters
+///
+/// - `folder_key`: The random key of the folder.
+/// - `file_path`: The `/` separated path of the file relative to the folder,
+///   which is unique in the folder.
+/// - `key_len`: The length of the derived key in bytes.
+/// - `nonce_len`: The length of the derived nonce in bytes.
+///
+/// # Returns
+///
+/// Returns the derived key and nonce. If they are too long for HKDF,
+/// `Err(KeyError::Derivation)` will be returned.
+pub fn derive_file_key(
+    folder_key: &EncryptionKey, file_path: &str, key_len: usize, nonce_len: usize,
+) -> Result<(EncryptionKey, EncryptionKey), KeyError> {
+    let info = [FILE_KEY_INFO, &[0], file_path.as_bytes()].concat();
+
+    let mut okm = vec![0u8; key_len + nonce_len];
+    Hkdf::<Sha256>::new(None, folder_key)
+        .expand(&info, &mut okm)
+        .map_err(|_| KeyError::Derivation)?;
+
+    let (key, nonce) = okm.split_at(key_len);
+    let derived = (EncryptionKey::from(key.to_vec()), EncryptionKey::from(nonce.to_vec()));
+    okm.zeroize();
+
+    Ok(derived)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARGON2_TEST: Kdf = Kdf::Argon2 {
+        m_cost: 64,
+        t_cost: 1,
+        p_cost: 1,
+    };
+
+    #[test]
+    fn test_argon2() {
+        let salt = EncryptionKey::random(KDF_SALT_LEN);
+
+        let key = ARGON2_TEST.derive(b"password", &salt, 32).unwrap();
+        assert_eq!(key.len(), 32);
+        assert_eq!(*key, *ARGON2_TEST.derive(b"password", &salt, 32).unwrap());
+        assert_ne!(*key, *ARGON2_TEST.derive(b"Password", &salt, 32).unwrap());
+    }
+
+    #[test]
+    fn test_pbkdf2() {
+        let kdf = Kdf::Pbkdf2 { rounds: 1000 };
+        let salt = EncryptionKey::random(KDF_SALT_LEN);
+
+        let key = kdf.derive(b"password", &salt, 32).unwrap();
+        assert_eq!(key.len(), 32);
+        assert_eq!(*key, *kdf.derive(b"password", &salt, 32).unwrap());
+        assert_ne!(*key, *kdf.derive(b"Password", &salt, 32).unwrap());
+    }
+
+    #[test]
+    fn test_derive_file_key() {
+        let folder_key = EncryptionKey::random(FOLDER_KEY_LEN);
+
+        let (key, nonce) = derive_file_key(&folder_key, "a.txt", 32, 12).unwrap();
+        assert_eq!((key.len(), nonce.len()), (32, 12));
+
+        let (same_key, same_nonce) = derive_file_key(&folder_key, "a.txt", 32, 12).unwrap();
+        assert_eq!(*key, *same_key);
+        assert_eq!(*nonce, *same_nonce);
+
+        let (other_key, other_nonce) = derive_file_key(&folder_key, "b.txt", 32, 12).unwrap();
+        assert_ne!(*key, *other_key);
+        assert_ne!(*nonce, *other_nonce);
+
+        assert_eq!(
+            derive_file_key(&folder_key, "a.txt", 255 * 32, 1).err(),
+            Some(KeyError::Derivation)
+        );
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        assert_eq!(
+            Kdf::Pbkdf2 { rounds: 0 }.derive(b"password", &[0; 16], 32).err(),
+            Some(KeyError::Derivation)
+        );
+        assert_eq!(
+            ARGON2_TEST.derive(b"password", &[0; 4], 32).err(),
+            Some(KeyError::Derivation)
+        );
+    }
+}
@@ -1,34 +1,85 @@
 use rand::{rngs::OsRng, Rng};
-use std::{convert::TryFrom, ops::Deref};
+use std::{convert::TryFrom, fmt, ops::Deref};
+use zeroize::Zeroize;
 
 /// The default key size.
 pub const DEFAULT_KEY_LEN: usize = super::xor::XOR_KEY_LEN;
 
+/// The errors that can occur while parsing a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyError {
+    /// The key is not a valid hex string.
+    Format,
+
+    /// The key is empty and can't be extended to the required length.
+    Length,
+
+    /// The key couldn't be derived, because the parameters of the key
+    /// derivation function are invalid.
+    Derivation,
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyError::Format => write!(f, "the key is not a valid hex string"),
+            KeyError::Length => write!(f, "the key is empty"),
+            KeyError::Derivation => write!(f, "the key derivation parameters are invalid"),
+        }
+    }
+}
+
+impl std::error::Error for KeyError {}
+
 /// A simple symmetric encryption key which will be stored as a vector of bytes.
+/// The bytes will be overwritten with zeros when the key is dropped.
 pub struct EncryptionKey {
     data: Vec<u8>,
 }
 
 impl EncryptionKey {
-    /// Creates a new key from the specified hex string.
-    pub fn new(key: &'_ str, key_len: usize) -> Result<Self, String> {
+    /// Creates a new key from the specified hex string. If the key doesn't have
+    /// the specified length, it will be repeated or truncated to `key_len`.
+    ///
+    /// Older versions always repeated the key to [`DEFAULT_KEY_LEN`] bytes,
+    /// regardless of `key_len`. Keys of ciphers with the default key length
+    /// are unchanged, for shorter lengths the new key is the prefix of the old
+    /// one and longer lengths aren't cut off at [`DEFAULT_KEY_LEN`] anymore.
+    pub fn new(key: &'_ str, key_len: usize) -> Result<Self, KeyError> { Self::from_hex(key)?.with_len(key_len) }
+
+    /// Creates a new key from the specified hex string without changing its
+    /// length.
+    pub fn from_hex(key: &'_ str) -> Result<Self, KeyError> {
         // Remove the optional trailing '0x' and convert to vector
         //
-        let mut key = hex::decode(key.trim_start_matches("0x")).map_err(|e| e.to_string())?;
+        let key = hex::decode(key.trim_start_matches("0x")).map_err(|_| KeyError::Format)?;
+
+        Ok(Self { data: key })
+    }
 
-        // Extend the key if it is smaller than the default key length.
+    /// Repeats or truncates the key, so that it has the specified length.
+    pub fn with_len(self, key_len: usize) -> Result<Self, KeyError> {
+        if self.data.len() == key_len {
+            return Ok(self);
+        }
+
+        // Extend the key if it is smaller than the key length.
         //
-        if key.len() != key_len {
-            key = key.into_iter().cycle().take(DEFAULT_KEY_LEN).collect::<Vec<_>>();
+        if self.data.is_empty() {
+            return Err(KeyError::Length);
         }
 
-        Ok(Self { data: key })
+        // The old key will be zeroized when it is dropped.
+        //
+        Ok(Self {
+            data: self.data.iter().copied().cycle().take(key_len).collect::<Vec<_>>(),
+        })
     }
 
     /// Generates a random key with the specified size.
     pub fn random(key_len: usize) -> Self {
         let mut key = vec![0u8; key_len];
-        let mut rng = OsRng::default();
+        let mut rng = OsRng;
         rng.fill(&mut key[..]);
 
         Self { data: key }
@@ -38,6 +89,10 @@ impl EncryptionKey {
     pub fn as_str(&self) -> String { hex::encode(&self.data) }
 }
 
+impl Drop for EncryptionKey {
+    fn drop(&mut self) { self.data.zeroize(); }
+}
+
 impl Default for EncryptionKey {
     fn default() -> Self { Self::random(DEFAULT_KEY_LEN) }
 }
@@ -49,17 +104,21 @@ impl Deref for EncryptionKey {
 }
 
 impl AsRef<EncryptionKey> for EncryptionKey {
-    fn as_ref(&self) -> &Self { &self }
+    fn as_ref(&self) -> &Self { self }
+}
+
+impl From<Vec<u8>> for EncryptionKey {
+    fn from(data: Vec<u8>) -> Self { Self { data } }
 }
 
 impl TryFrom<String> for EncryptionKey {
-    type Error = String;
+    type Error = KeyError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> { Self::new(value.as_str(), DEFAULT_KEY_LEN) }
 }
 
 impl TryFrom<&str> for EncryptionKey {
-    type Error = String;
+    type Error = KeyError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> { Self::new(value, DEFAULT_KEY_LEN) }
 }
@@ -84,8 +143,18 @@ mod tests {
         assert_eq!(key.data.len(), 6);
         assert_eq!(key.data, vec![0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff]);
 
-        assert_eq!(EncryptionKey::try_from("0xa").is_err(), true);
-        assert_eq!(EncryptionKey::try_from("0xaab").is_err(), true);
+        assert_eq!(EncryptionKey::try_from("0xa").err(), Some(KeyError::Format));
+        assert_eq!(EncryptionKey::try_from("0xaab").err(), Some(KeyError::Format));
+    }
+
+    #[test]
+    fn test_key_errors() {
+        assert_eq!(EncryptionKey::try_from("0xzz").err(), Some(KeyError::Format));
+        assert_eq!(EncryptionKey::try_from("0x").err(), Some(KeyError::Length));
+        assert_eq!(EncryptionKey::new("", 0).map(|key| key.len()), Ok(0));
+
+        let key = EncryptionKey::new("0xaabbccddeeff", 4).unwrap();
+        assert_eq!(key.data, vec![0xaa, 0xbb, 0xcc, 0xdd]);
     }
 
     #[test]
@@ -0,0 +1,216 @@
+use crate::{
+    key::{EncryptionKey, KeyError},
+    provider::KeyProvider,
+};
+use std::{collections::BTreeMap, fmt, path::Path, str::FromStr};
+
+/// The errors that can occur while parsing a keyring.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyringError {
+    /// The keyring file couldn't be read.
+    Io(String),
+
+    /// The line is not a `key_id = "key"` pair.
+    Syntax { line: usize },
+
+    /// The key on the line is not a valid hex string.
+    Key { line: usize, error: KeyError },
+
+    /// The key id on the line has already been defined.
+    Duplicate { line: usize, key_id: String },
+}
+
+impl fmt::Display for KeyringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyringError::Io(e) => write!(f, "the keyring couldn't be read: {}", e),
+            KeyringError::Syntax { line } => write!(f, "line {} of the keyring is not a `key_id = \"key\"` pair", line),
+            KeyringError::Key { line, error } => {
+                write!(f, "the key on line {} of the keyring is invalid: {}", line, error)
+            }
+            KeyringError::Duplicate { line, key_id } => {
+                write!(
+                    f,
+                    "the key id {:?} on line {} of the keyring is already defined",
+                    key_id, line
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for KeyringError {}
+
+/// Several keys by their id, so that keys can be rotated without re-embedding
+/// all the files at once. Files are embedded with the id of the current key
+/// and decrypted with the key of their id.
+///
+/// The keyring file has one hex encoded key per line. Empty lines and lines
+/// starting with `#` are ignored:
+///
+/// ```text
+/// # Old keys are kept until all the files have been re-encrypted.
+/// "2026-q2" = "5f1c..."
+/// "2026-q3" = "0x9a4e..."
+/// ```
+#[derive(Default)]
+pub struct Keyring {
+    keys: BTreeMap<String, EncryptionKey>,
+}
+
+impl Keyring {
+    /// Creates an empty keyring.
+    pub fn new() -> Self { Self::default() }
+
+    /// Reads the keyring from the specified file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, KeyringError> {
+        let content = std::fs::read_to_string(path).map_err(|e| KeyringError::Io(e.to_string()))?;
+
+        content.parse()
+    }
+
+    /// Adds the key with the specified id. If the id already exists, the old
+    /// key will be replaced and returned.
+    pub fn insert<S: Into<String>>(&mut self, key_id: S, key: EncryptionKey) -> Option<EncryptionKey> {
+        self.keys.insert(key_id.into(), key)
+    }
+
+    /// Removes the key with the specified id and returns it.
+    pub fn remove(&mut self, key_id: &str) -> Option<EncryptionKey> { self.keys.remove(key_id) }
+
+    /// Returns the key with the specified id.
+    pub fn get(&self, key_id: &str) -> Option<&EncryptionKey> { self.keys.get(key_id) }
+
+    /// Returns whether the keyring contains a key with the specified id.
+    pub fn contains(&self, key_id: &str) -> bool { self.keys.contains_key(key_id) }
+
+    /// Returns the ids of all the keys in sorted order.
+    pub fn key_ids(&self) -> impl Iterator<Item = &str> { self.keys.keys().map(String::as_str) }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize { self.keys.len() }
+
+    /// Returns whether the keyring has no keys.
+    pub fn is_empty(&self) -> bool { self.keys.is_empty() }
+}
+
+impl FromStr for Keyring {
+    type Err = KeyringError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let mut keyring = Self::new();
+        for (i, line) in content.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key_id, key) = line
+                .split_once('=')
+                .map(|(key_id, key)| (unquote(key_id), unquote(key)))
+                .filter(|(key_id, _)| !key_id.is_empty())
+                .ok_or(KeyringError::Syntax { line: line_number })?;
+
+            let key = EncryptionKey::from_hex(key)
+                .and_then(|key| if key.is_empty() { Err(KeyError::Length) } else { Ok(key) })
+                .map_err(|error| KeyringError::Key {
+                    line: line_number,
+                    error,
+                })?;
+            if keyring.insert(key_id, key).is_some() {
+                return Err(KeyringError::Duplicate {
+                    line: line_number,
+                    key_id: key_id.to_string(),
+                });
+            }
+        }
+
+        Ok(keyring)
+    }
+}
+
+/// Removes the whitespace and the optional quotes around the value.
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+impl KeyProvider for Keyring {
+    fn key(&self, key_id: &str) -> Option<EncryptionKey> {
+        self.get(key_id).map(|key| EncryptionKey::from(key.to_vec()))
+    }
+}
+
+/// The keys are never printed.
+impl fmt::Debug for Keyring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keyring")
+            .field("key_ids", &self.key_ids().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let keyring = "# Comment\n\n\"2026-q2\" = \"aabbccdd\"\n2026-q3 = 0x11223344\n"
+            .parse::<Keyring>()
+            .unwrap();
+
+        assert_eq!(keyring.key_ids().collect::<Vec<_>>(), vec!["2026-q2", "2026-q3"]);
+        assert_eq!(**keyring.get("2026-q2").unwrap(), vec![0xaa, 0xbb, 0xcc, 0xdd]);
+        assert_eq!(*keyring.key("2026-q3").unwrap(), vec![0x11, 0x22, 0x33, 0x44]);
+        assert!(keyring.key("2026-q4").is_none());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "\n\"2026-q2\"\n".parse::<Keyring>().err(),
+            Some(KeyringError::Syntax { line: 2 })
+        );
+        assert_eq!(
+            "a = \"zz\"".parse::<Keyring>().err(),
+            Some(KeyringError::Key {
+                line: 1,
+                error: KeyError::Format
+            })
+        );
+        assert_eq!(
+            "a = \"\"".parse::<Keyring>().err(),
+            Some(KeyringError::Key {
+                line: 1,
+                error: KeyError::Length
+            })
+        );
+        assert_eq!(
+            "a = aa\na = bb".parse::<Keyring>().err(),
+            Some(KeyringError::Duplicate {
+                line: 2,
+                key_id: "a".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut keyring = Keyring::new();
+        assert!(keyring.is_empty());
+
+        assert!(keyring.insert("old", EncryptionKey::from(vec![1])).is_none());
+        assert!(keyring.insert("new", EncryptionKey::from(vec![2])).is_none());
+        assert_eq!(keyring.len(), 2);
+
+        assert_eq!(*keyring.remove("old").unwrap(), vec![1]);
+        assert!(!keyring.contains("old"));
+        assert!(keyring.contains("new"));
+    }
+}
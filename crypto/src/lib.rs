@@ -1,3 +1,15 @@
 pub mod aes;
+pub mod aes_gcm;
+pub mod archive;
+pub mod chacha;
+pub mod cipher;
+pub mod compression;
+pub mod envelope;
+pub mod kdf;
 pub mod key;
+pub mod keyring;
+pub mod mac;
+pub mod provider;
+pub mod signature;
+pub mod x25519;
 pub mod xor;
@@ -0,0 +1,76 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Key length of the mac (HMAC-SHA256)
+pub const MAC_KEY_LEN: usize = 32;
+
+/// Tag length of the mac (HMAC-SHA256)
+pub const MAC_TAG_LEN: usize = 32;
+
+/// Computes the HMAC-SHA256 of the data.
+///
+/// # Parameters
+///
+/// - `data`: The data that should be authenticated. This is the encrypted
+///   buffer, so that it can be verified before decrypting it.
+/// - `key`: The key of the mac. It can have any length.
+///
+/// # Returns
+///
+/// Returns the mac of the data.
+pub fn hmac_sha256(data: &[u8], key: &[u8]) -> [u8; MAC_TAG_LEN] {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+
+    mac.finalize().into_bytes().into()
+}
+
+/// Verifies the HMAC-SHA256 of the data in constant time.
+///
+/// # Parameters
+///
+/// - `data`: The data that has been authenticated.
+/// - `key`: The key of the mac.
+/// - `tag`: The mac that has been returned by [`hmac_sha256`].
+///
+/// # Returns
+///
+/// Returns `true` if the mac matches the data.
+pub fn hmac_verify(data: &[u8], key: &[u8], tag: &[u8]) -> bool {
+    match <Hmac<Sha256> as Mac>::new_from_slice(key) {
+        Ok(mut mac) => {
+            mac.update(data);
+            mac.verify_slice(tag).is_ok()
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::EncryptionKey;
+
+    #[test]
+    fn test_hmac() {
+        let data = "The quick brown fox jumps over the lazy dog.".as_bytes();
+        let key = EncryptionKey::random(MAC_KEY_LEN);
+
+        let tag = hmac_sha256(data, &key);
+        assert!(hmac_verify(data, &key, &tag));
+        assert!(!hmac_verify(&data[1..], &key, &tag));
+        assert!(!hmac_verify(data, &EncryptionKey::random(MAC_KEY_LEN), &tag));
+        assert!(!hmac_verify(data, &key, &tag[1..]));
+    }
+
+    #[test]
+    fn test_hmac_rfc4231() {
+        // Test case 2 of RFC 4231.
+        //
+        let tag = hmac_sha256(b"what do ya want for nothing?", b"Jefe");
+        assert_eq!(
+            hex::encode(tag),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
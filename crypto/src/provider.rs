@@ -0,0 +1,159 @@
+use crate::key::EncryptionKey;
+use std::path::PathBuf;
+
+/// The prefix of the environment variables that are used by [`EnvProvider`].
+pub const KEY_ENV_PREFIX: &str = "INCLUDE_CRYPT_KEY_";
+
+/// Supplies the keys of files that have been embedded with a key id. The key
+/// is not stored in the binary, so it has to be fetched at runtime.
+pub trait KeyProvider {
+    /// Returns the key for the specified key id.
+    ///
+    /// # Returns
+    ///
+    /// If the provider has no (valid) key for the key id, `None` will be
+    /// returned.
+    fn key(&self, key_id: &str) -> Option<EncryptionKey>;
+}
+
+/// Closures can be used as key providers.
+impl<F: Fn(&str) -> Option<EncryptionKey>> KeyProvider for F {
+    fn key(&self, key_id: &str) -> Option<EncryptionKey> { self(key_id) }
+}
+
+/// Reads the hex encoded keys from environment variables. The name of the
+/// variable is returned by [`EnvProvider::var_name`].
+pub struct EnvProvider;
+
+impl EnvProvider {
+    /// Returns the name of the environment variable for the key id, e.g.
+    /// `INCLUDE_CRYPT_KEY_MY_KEY` for `my-key`. The proc macros use the same
+    /// variable if no key has been specified.
+    pub fn var_name(key_id: &str) -> String {
+        let key_id = key_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+
+        format!("{}{}", KEY_ENV_PREFIX, key_id)
+    }
+}
+
+impl KeyProvider for EnvProvider {
+    fn key(&self, key_id: &str) -> Option<EncryptionKey> {
+        let key = std::env::var(Self::var_name(key_id)).ok()?;
+        EncryptionKey::from_hex(key.trim()).ok().filter(|key| !key.is_empty())
+    }
+}
+
+/// Reads the hex encoded keys from the files in a directory. The name of the
+/// file is the key id.
+pub struct FileProvider {
+    directory: PathBuf,
+}
+
+impl FileProvider {
+    /// Creates a new provider that reads the keys from the specified directory.
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+}
+
+impl KeyProvider for FileProvider {
+    fn key(&self, key_id: &str) -> Option<EncryptionKey> {
+        // Don't allow the key id to escape the directory.
+        //
+        if key_id.contains(['/', '\\']) || key_id.starts_with('.') {
+            return None;
+        }
+
+        let key = std::fs::read_to_string(self.directory.join(key_id)).ok()?;
+        EncryptionKey::from_hex(key.trim()).ok().filter(|key| !key.is_empty())
+    }
+}
+
+/// Requests the keys from a local agent over a unix socket. The key id is sent
+/// as a single line and the agent answers with the hex encoded key on a single
+/// line. An empty line means that the agent has no key for the key id.
+#[cfg(unix)]
+pub struct AgentProvider {
+    socket: PathBuf,
+}
+
+#[cfg(unix)]
+impl AgentProvider {
+    /// Creates a new provider that connects to the specified socket.
+    pub fn new<P: Into<PathBuf>>(socket: P) -> Self { Self { socket: socket.into() } }
+}
+
+#[cfg(unix)]
+impl KeyProvider for AgentProvider {
+    fn key(&self, key_id: &str) -> Option<EncryptionKey> {
+        use std::{
+            io::{BufRead, BufReader, Write},
+            os::unix::net::UnixStream,
+        };
+
+        if key_id.contains('\n') {
+            return None;
+        }
+
+        let mut stream = UnixStream::connect(&self.socket).ok()?;
+        stream.write_all(format!("{}\n", key_id).as_bytes()).ok()?;
+
+        let mut key = String::new();
+        BufReader::new(stream).read_line(&mut key).ok()?;
+
+        EncryptionKey::from_hex(key.trim()).ok().filter(|key| !key.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_provider() {
+        assert_eq!(EnvProvider::var_name("my-key.v2"), "INCLUDE_CRYPT_KEY_MY_KEY_V2");
+
+        std::env::set_var(EnvProvider::var_name("test_env_provider"), "0xaabbccdd");
+        assert_eq!(
+            *EnvProvider.key("test_env_provider").unwrap(),
+            vec![0xaa, 0xbb, 0xcc, 0xdd]
+        );
+        assert!(EnvProvider.key("test_env_provider_missing").is_none());
+    }
+
+    #[test]
+    fn test_file_provider() {
+        let directory = std::env::temp_dir().join(format!("include-crypt-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("file_key"), "aabbccdd\n").unwrap();
+
+        let provider = FileProvider::new(&directory);
+        assert_eq!(*provider.key("file_key").unwrap(), vec![0xaa, 0xbb, 0xcc, 0xdd]);
+        assert!(provider.key("missing_key").is_none());
+        assert!(provider.key("../file_key").is_none());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_fn_provider() {
+        let provider = |key_id: &str| match key_id {
+            "callback" => Some(EncryptionKey::from(vec![1, 2, 3])),
+            _ => None,
+        };
+
+        assert_eq!(*provider.key("callback").unwrap(), vec![1, 2, 3]);
+        assert!(provider.key("other").is_none());
+    }
+}
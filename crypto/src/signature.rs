@@ -0,0 +1,143 @@
+use crate::key::{EncryptionKey, KeyError};
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use rand::{rngs::OsRng, Rng};
+use sha2::{Digest, Sha256};
+use std::convert::TryFrom;
+
+/// Key length of the secret and public keys (Ed25519)
+pub const ED25519_KEY_LEN: usize = 32;
+
+/// Signature length (Ed25519)
+pub const ED25519_SIGNATURE_LEN: usize = 64;
+
+/// The public key which is used to verify the signatures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey {
+    data: [u8; ED25519_KEY_LEN],
+}
+
+impl PublicKey {
+    /// Creates a new public key from the bytes.
+    pub const fn new(data: [u8; ED25519_KEY_LEN]) -> Self { Self { data } }
+
+    /// Creates a new public key from the specified hex string.
+    pub fn from_hex(key: &'_ str) -> Result<Self, KeyError> {
+        let key = EncryptionKey::from_hex(key)?;
+        let data = <[u8; ED25519_KEY_LEN]>::try_from(&key[..]).map_err(|_| KeyError::Length)?;
+
+        Ok(Self { data })
+    }
+
+    /// Returns the bytes of the public key.
+    pub fn as_bytes(&self) -> &[u8; ED25519_KEY_LEN] { &self.data }
+}
+
+/// Generates a random key pair.
+///
+/// # Returns
+///
+/// Returns the secret key and the public key.
+pub fn ed25519_keypair() -> (EncryptionKey, PublicKey) {
+    let mut secret = [0u8; ED25519_KEY_LEN];
+    OsRng.fill(&mut secret);
+    let public = SigningKey::from_bytes(&secret).verifying_key();
+
+    (EncryptionKey::from(secret.to_vec()), PublicKey::new(public.to_bytes()))
+}
+
+/// Signs the data with the secret key.
+///
+/// # Parameters
+///
+/// - `data`: The data that should be signed.
+/// - `secret_key`: The secret key. It must be exactly 32 bytes.
+///
+/// # Returns
+///
+/// Returns the signature and the public key of the secret key. If the secret
+/// key doesn't have a length of 32 bytes, `Err(KeyError::Length)` will be
+/// returned.
+pub fn ed25519_sign(data: &[u8], secret_key: &[u8]) -> Result<([u8; ED25519_SIGNATURE_LEN], PublicKey), KeyError> {
+    let secret = <[u8; ED25519_KEY_LEN]>::try_from(secret_key).map_err(|_| KeyError::Length)?;
+    let key = SigningKey::from_bytes(&secret);
+
+    Ok((
+        key.sign(data).to_bytes(),
+        PublicKey::new(key.verifying_key().to_bytes()),
+    ))
+}
+
+/// Verifies the signature of the data.
+///
+/// # Parameters
+///
+/// - `data`: The data that has been signed.
+/// - `public_key`: The public key of the signer.
+/// - `signature`: The signature that has been returned by [`ed25519_sign`].
+///
+/// # Returns
+///
+/// Returns `true` if the signature is valid.
+pub fn ed25519_verify(data: &[u8], public_key: &PublicKey, signature: &[u8]) -> bool {
+    let signature = match ed25519_dalek::Signature::from_slice(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+
+    VerifyingKey::from_bytes(public_key.as_bytes())
+        .and_then(|key| key.verify_strict(data, &signature))
+        .is_ok()
+}
+
+/// Creates the manifest of multiple files, so that they can be signed at
+/// once. The manifest contains the name and the SHA-256 hash of each file.
+///
+/// # Parameters
+///
+/// - `entries`: The names and contents of the files.
+pub fn ed25519_manifest<'a, I: IntoIterator<Item = (&'a str, &'a [u8])>>(entries: I) -> Vec<u8> {
+    let mut manifest = Vec::new();
+    for (name, data) in entries {
+        manifest.extend_from_slice(&(name.len() as u64).to_le_bytes());
+        manifest.extend_from_slice(name.as_bytes());
+        manifest.extend_from_slice(&Sha256::digest(data));
+    }
+
+    manifest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ed25519() {
+        let data = "The quick brown fox jumps over the lazy dog.".as_bytes();
+        let (secret, public) = ed25519_keypair();
+
+        let (signature, signer) = ed25519_sign(data, &secret).unwrap();
+        assert_eq!(signer, public);
+        assert!(ed25519_verify(data, &public, &signature));
+
+        assert!(!ed25519_verify(b"The quick brown fox", &public, &signature));
+        assert!(!ed25519_verify(data, &ed25519_keypair().1, &signature));
+        assert!(!ed25519_verify(data, &public, &signature[1..]));
+    }
+
+    #[test]
+    fn test_ed25519_manifest() {
+        let manifest = ed25519_manifest(vec![("a", &b"1"[..]), ("b", &b"2"[..])]);
+
+        assert_ne!(manifest, ed25519_manifest(vec![("a", &b"1"[..]), ("b", &b"3"[..])]));
+        assert_ne!(manifest, ed25519_manifest(vec![("a", &b"1"[..]), ("c", &b"2"[..])]));
+        assert_ne!(manifest, ed25519_manifest(vec![("a", &b"1"[..])]));
+    }
+
+    #[test]
+    fn test_public_key() {
+        let (_, public) = ed25519_keypair();
+
+        assert_eq!(PublicKey::from_hex(&hex::encode(public.as_bytes())), Ok(public));
+        assert_eq!(PublicKey::from_hex("0xaabb"), Err(KeyError::Length));
+    }
+}
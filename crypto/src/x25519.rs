@@ -0,0 +1,211 @@
+use crate::{
+    chacha::{chacha_decrypt, chacha_encrypt, CHACHA_KEY_LEN, CHACHA_NONCE_LEN, CHACHA_TAG_LEN},
+    cipher::{Cipher, CipherError},
+    key::EncryptionKey,
+};
+use hkdf::Hkdf;
+use rand::{rngs::OsRng, Rng};
+use sha2::Sha256;
+use std::convert::{TryFrom, TryInto};
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroize;
+
+/// Key length of the secret and public keys (X25519)
+pub const X25519_KEY_LEN: usize = 32;
+
+/// Authentication tag length (X25519 sealed box)
+pub const X25519_TAG_LEN: usize = CHACHA_TAG_LEN;
+
+/// The info string that is used to derive the key and nonce.
+const X25519_INFO: &[u8] = b"include-crypt x25519 sealed box";
+
+/// Generates a random key pair.
+///
+/// # Returns
+///
+/// Returns the secret key and the public key.
+pub fn x25519_keypair() -> (EncryptionKey, EncryptionKey) {
+    let mut secret = [0u8; X25519_KEY_LEN];
+    OsRng.fill(&mut secret);
+    let public = PublicKey::from(&StaticSecret::from(secret));
+    let keypair = (
+        EncryptionKey::from(secret.to_vec()),
+        EncryptionKey::from(public.as_bytes().to_vec()),
+    );
+    secret.zeroize();
+
+    keypair
+}
+
+/// Returns the public key of the secret key. If the secret key doesn't have a
+/// length of [`X25519_KEY_LEN`], `None` will be returned.
+pub fn x25519_public_key(secret_key: &[u8]) -> Option<EncryptionKey> {
+    let secret = StaticSecret::from(<[u8; X25519_KEY_LEN]>::try_from(secret_key).ok()?);
+
+    Some(EncryptionKey::from(PublicKey::from(&secret).as_bytes().to_vec()))
+}
+
+/// Derives the symmetric key and nonce from the key exchange.
+///
+/// # Parameters
+///
+/// - `secret_key`: The own secret key.
+/// - `public_key`: The public key of the other party.
+/// - `ephemeral`: Whether the own key is the ephemeral key of the sender.
+///
+/// # Returns
+///
+/// If one of the keys is invalid, `None` will be returned.
+#[inline(always)]
+fn x25519_derive(secret_key: &[u8], public_key: &[u8], ephemeral: bool) -> Option<(EncryptionKey, EncryptionKey)> {
+    let secret = StaticSecret::from(<[u8; X25519_KEY_LEN]>::try_from(secret_key).ok()?);
+    let public = PublicKey::from(<[u8; X25519_KEY_LEN]>::try_from(public_key).ok()?);
+
+    let shared = secret.diffie_hellman(&public);
+    if !shared.was_contributory() {
+        return None;
+    }
+
+    // Bind the derived key to the ephemeral and recipient public key.
+    //
+    let own = PublicKey::from(&secret);
+    let (sender, recipient) = if ephemeral { (&own, &public) } else { (&public, &own) };
+    let salt = [sender.as_bytes().as_ref(), recipient.as_bytes().as_ref()].concat();
+
+    let mut okm = [0u8; CHACHA_KEY_LEN + CHACHA_NONCE_LEN];
+    Hkdf::<Sha256>::new(Some(&salt), shared.as_bytes())
+        .expand(X25519_INFO, &mut okm)
+        .ok()?;
+
+    let (key, nonce) = okm.split_at(CHACHA_KEY_LEN);
+    let derived = (EncryptionKey::from(key.to_vec()), EncryptionKey::from(nonce.to_vec()));
+    okm.zeroize();
+
+    Some(derived)
+}
+
+/// Encrypts the specified data to the public key of the recipient.
+///
+/// # Parameters
+///
+/// - `data`: The plaintext data buffer. After this function has been called, it
+///   will store the encrypted data.
+/// - `public_key`: The public key of the recipient. It must be exactly 32
+///   bytes.
+///
+/// # Returns
+///
+/// If the data could be successfully encrypted, the ephemeral public key and
+/// the authentication tag will be returned. If the public key is invalid,
+/// `Err(CipherError::InvalidLength)` will be returned.
+pub fn x25519_seal(
+    data: &mut [u8], public_key: &[u8],
+) -> Result<([u8; X25519_KEY_LEN], [u8; X25519_TAG_LEN]), CipherError> {
+    let (secret, ephemeral) = x25519_keypair();
+    let tag = X25519.encrypt(data, &secret, &EncryptionKey::from(public_key.to_vec()))?;
+
+    Ok((
+        ephemeral[..].try_into().map_err(|_| CipherError::InvalidLength)?,
+        tag[..].try_into().map_err(|_| CipherError::InvalidLength)?,
+    ))
+}
+
+/// Decrypts the data that has been encrypted with [`x25519_seal`].
+///
+/// # Parameters
+///
+/// - `data`: The encrypted data buffer. After this function has been called, it
+///   will store the decrypted data.
+/// - `secret_key`: The secret key of the recipient. It must be exactly 32
+///   bytes.
+/// - `ephemeral`: The ephemeral public key that has been returned by
+///   [`x25519_seal`].
+/// - `tag`: The authentication tag that has been returned by [`x25519_seal`].
+///
+/// # Returns
+///
+/// If the data could be successfully decrypted `Ok(())` will be returned. If
+/// the secret key is wrong or the data has been tampered with,
+/// `Err(CipherError::Integrity)` will be returned.
+pub fn x25519_open(data: &mut [u8], secret_key: &[u8], ephemeral: &[u8], tag: &[u8]) -> Result<(), CipherError> {
+    X25519.decrypt(
+        data,
+        &EncryptionKey::from(secret_key.to_vec()),
+        &EncryptionKey::from(ephemeral.to_vec()),
+        tag,
+    )
+}
+
+/// The X25519 sealed box which can be used through the [`Cipher`] trait. The
+/// key is the own secret key and the nonce is the public key of the other
+/// party: When encrypting, the key is the ephemeral secret key and the nonce
+/// the public key of the recipient. When decrypting, the key is the secret key
+/// of the recipient and the nonce the ephemeral public key. An ephemeral secret
+/// key must only be used once.
+pub struct X25519;
+
+impl Cipher for X25519 {
+    fn key_len(&self) -> usize { X25519_KEY_LEN }
+
+    fn nonce_len(&self) -> usize { X25519_KEY_LEN }
+
+    fn tag_len(&self) -> usize { X25519_TAG_LEN }
+
+    fn encrypt(&self, data: &mut [u8], key: &EncryptionKey, nonce: &EncryptionKey) -> Result<Vec<u8>, CipherError> {
+        let (key, nonce) = x25519_derive(key, nonce, true).ok_or(CipherError::InvalidLength)?;
+
+        chacha_encrypt(data, &key, &nonce)
+            .map(|tag| tag.to_vec())
+            .map_err(|_| CipherError::InvalidLength)
+    }
+
+    fn decrypt(
+        &self, data: &mut [u8], key: &EncryptionKey, nonce: &EncryptionKey, tag: &[u8],
+    ) -> Result<(), CipherError> {
+        if key.len() != X25519_KEY_LEN || nonce.len() != X25519_KEY_LEN || tag.len() != X25519_TAG_LEN {
+            return Err(CipherError::InvalidLength);
+        }
+
+        let (key, nonce) = x25519_derive(key, nonce, false).ok_or(CipherError::Integrity)?;
+        chacha_decrypt(data, &key, &nonce, tag).map_err(|_| CipherError::Integrity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_x25519() {
+        let mut data = Vec::from("The quick brown fox jumps over the lazy dog.".as_bytes());
+        let (secret, public) = x25519_keypair();
+
+        let (ephemeral, tag) = x25519_seal(data.as_mut_slice(), &public).unwrap();
+        assert_ne!(data, "The quick brown fox jumps over the lazy dog.".as_bytes());
+
+        assert!(x25519_open(data.as_mut_slice(), &secret, &ephemeral, &tag).is_ok());
+        assert_eq!(data, "The quick brown fox jumps over the lazy dog.".as_bytes());
+    }
+
+    #[test]
+    fn test_x25519_wrong_key() {
+        let mut data = Vec::from("The quick brown fox jumps over the lazy dog.".as_bytes());
+        let (_, public) = x25519_keypair();
+        let (secret, _) = x25519_keypair();
+
+        let (ephemeral, tag) = x25519_seal(data.as_mut_slice(), &public).unwrap();
+        assert_eq!(
+            x25519_open(data.as_mut_slice(), &secret, &ephemeral, &tag),
+            Err(CipherError::Integrity)
+        );
+    }
+
+    #[test]
+    fn test_x25519_invalid_key() {
+        assert_eq!(x25519_seal(&mut [0u8; 4], &[0u8; 16]), Err(CipherError::InvalidLength));
+        assert_eq!(
+            x25519_seal(&mut [0u8; 4], &[0u8; X25519_KEY_LEN]),
+            Err(CipherError::InvalidLength)
+        );
+    }
+}
@@ -1,8 +1,18 @@
-use crate::key::EncryptionKey;
+use crate::{
+    cipher::{Cipher, CipherError},
+    key::EncryptionKey,
+};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::convert::TryInto;
+use zeroize::Zeroize;
 
 /// Default xor key length.
 pub const XOR_KEY_LEN: usize = 32;
 
+/// Nonce length of the keystream xor.
+pub const XOR_NONCE_LEN: usize = 8;
+
 /// Encrypts the specified data with the key.
 ///
 /// # Parameters
@@ -18,6 +28,95 @@ pub fn xor<K: AsRef<EncryptionKey>>(data: &mut [u8], key: K) {
         .for_each(|d| d.iter_mut().zip(&**key).for_each(|(d, k)| *d ^= *k));
 }
 
+/// Encrypts the specified data with a keystream that is generated from the key
+/// and nonce. Unlike [`xor`], the keystream doesn't repeat, so the key can't be
+/// recovered from a single known plaintext block.
+///
+/// # Parameters
+///
+/// - `data`: The data buffer which can be either encrypted or decrypted. After
+///   this function has been called, it will store the encrypted/decrypted data.
+/// - `key`: The key that is used to seed the keystream. Keys that are shorter
+///   than 32 bytes will be repeated.
+/// - `nonce`: The unique nonce which selects the keystream. It must be exactly
+///   8 bytes.
+///
+/// # Returns
+///
+/// If the data could be successfully encrypted/decrypted `Ok(())` will be
+/// returned. If the key or nonce are invalid, `Err(CipherError)` will be
+/// returned.
+#[inline(always)]
+pub fn xor_stream<K: AsRef<EncryptionKey>>(data: &mut [u8], key: K, nonce: K) -> Result<(), CipherError> {
+    let (key, nonce) = (key.as_ref(), nonce.as_ref());
+    if key.is_empty() {
+        return Err(CipherError::InvalidLength);
+    }
+
+    let nonce: [u8; XOR_NONCE_LEN] = nonce[..].try_into().map_err(|_| CipherError::InvalidLength)?;
+
+    let mut seed = [0u8; XOR_KEY_LEN];
+    seed.iter_mut().zip(key.iter().cycle()).for_each(|(s, k)| *s = *k);
+
+    let mut rng = ChaCha8Rng::from_seed(seed);
+    rng.set_stream(u64::from_le_bytes(nonce));
+
+    let mut keystream = [0u8; 64];
+    data.chunks_mut(keystream.len()).for_each(|d| {
+        rng.fill_bytes(&mut keystream);
+        d.iter_mut().zip(&keystream).for_each(|(d, k)| *d ^= *k);
+    });
+
+    seed.zeroize();
+    keystream.zeroize();
+
+    Ok(())
+}
+
+/// The xor cipher which can be used through the [`Cipher`] trait.
+pub struct Xor;
+
+impl Cipher for Xor {
+    fn key_len(&self) -> usize { XOR_KEY_LEN }
+
+    fn encrypt(&self, data: &mut [u8], key: &EncryptionKey, _: &EncryptionKey) -> Result<Vec<u8>, CipherError> {
+        if key.is_empty() {
+            return Err(CipherError::InvalidLength);
+        }
+
+        xor(data, key);
+        Ok(Vec::new())
+    }
+
+    fn decrypt(&self, data: &mut [u8], key: &EncryptionKey, _: &EncryptionKey, _: &[u8]) -> Result<(), CipherError> {
+        if key.is_empty() {
+            return Err(CipherError::InvalidLength);
+        }
+
+        xor(data, key);
+        Ok(())
+    }
+}
+
+/// The keystream xor cipher which can be used through the [`Cipher`] trait.
+pub struct XorStream;
+
+impl Cipher for XorStream {
+    fn key_len(&self) -> usize { XOR_KEY_LEN }
+
+    fn nonce_len(&self) -> usize { XOR_NONCE_LEN }
+
+    fn encrypt(&self, data: &mut [u8], key: &EncryptionKey, nonce: &EncryptionKey) -> Result<Vec<u8>, CipherError> {
+        xor_stream(data, key, nonce).map(|_| Vec::new())
+    }
+
+    fn decrypt(
+        &self, data: &mut [u8], key: &EncryptionKey, nonce: &EncryptionKey, _: &[u8],
+    ) -> Result<(), CipherError> {
+        xor_stream(data, key, nonce)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -32,4 +131,40 @@ mod tests {
 
         assert_eq!(data, b"Hello World");
     }
+
+    #[test]
+    fn test_xor_stream() {
+        let mut data = Vec::from("Hello World".as_bytes());
+        let key = EncryptionKey::new("0xdeadbeef", XOR_KEY_LEN).unwrap();
+        let nonce = EncryptionKey::random(XOR_NONCE_LEN);
+
+        assert!(xor_stream(data.as_mut_slice(), &key, &nonce).is_ok());
+        assert_ne!(data, b"Hello World");
+        assert!(xor_stream(data.as_mut_slice(), &key, &nonce).is_ok());
+
+        assert_eq!(data, b"Hello World");
+    }
+
+    #[test]
+    fn test_xor_stream_no_repeat() {
+        let mut data = vec![0u8; XOR_KEY_LEN * 4];
+        let key = EncryptionKey::random(XOR_KEY_LEN);
+        let nonce = EncryptionKey::random(XOR_NONCE_LEN);
+
+        // With a zeroed buffer the output is the keystream itself, which must not
+        // repeat the key like the plain xor does.
+        //
+        assert!(xor_stream(data.as_mut_slice(), &key, &nonce).is_ok());
+        assert_ne!(&data[..XOR_KEY_LEN], &key[..]);
+        assert_ne!(&data[..XOR_KEY_LEN], &data[XOR_KEY_LEN..XOR_KEY_LEN * 2]);
+
+        // Another nonce selects another keystream.
+        //
+        let mut other = vec![0u8; XOR_KEY_LEN * 4];
+        let nonce = EncryptionKey::random(XOR_NONCE_LEN);
+        assert!(xor_stream(other.as_mut_slice(), &key, &nonce).is_ok());
+        assert_ne!(data, other);
+
+        assert!(xor_stream(data.as_mut_slice(), &key, &EncryptionKey::random(4)).is_err());
+    }
 }
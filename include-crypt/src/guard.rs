@@ -0,0 +1,53 @@
+use std::{fmt, ops::Deref, str::Utf8Error};
+use zeroize::Zeroizing;
+
+/// The decrypted buffer of an embedded file. The plaintext only exists as long
+/// as the guard and will be overwritten with zeros when it is dropped.
+///
+/// The guard is returned by [`EncryptedFile::decrypt_guard`]. The buffers of
+/// files which have to be decrypted with a key that is supplied at runtime can
+/// be moved into a guard without copying them:
+///
+/// ```ignore
+/// let guard = DecryptedGuard::from(file.decrypt_with_password(&password)?);
+/// ```
+///
+/// [`EncryptedFile::decrypt_guard`]: crate::EncryptedFile::decrypt_guard
+pub struct DecryptedGuard {
+    data: Zeroizing<Vec<u8>>,
+}
+
+impl DecryptedGuard {
+    /// Returns the decrypted buffer as string.
+    ///
+    /// # Returns
+    ///
+    /// If the decrypted buffer is not a valid utf-8 string, an error will be
+    /// returned. If it is a valid utf-8 string, it will be returned.
+    pub fn as_str(&self) -> Result<&str, Utf8Error> { std::str::from_utf8(&self.data) }
+}
+
+impl Deref for DecryptedGuard {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target { &self.data }
+}
+
+impl AsRef<[u8]> for DecryptedGuard {
+    fn as_ref(&self) -> &[u8] { &self.data }
+}
+
+impl From<Zeroizing<Vec<u8>>> for DecryptedGuard {
+    fn from(data: Zeroizing<Vec<u8>>) -> Self { Self { data } }
+}
+
+impl From<Vec<u8>> for DecryptedGuard {
+    fn from(data: Vec<u8>) -> Self { Self::from(Zeroizing::new(data)) }
+}
+
+/// The plaintext is never printed.
+impl fmt::Debug for DecryptedGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecryptedGuard").field("len", &self.data.len()).finish()
+    }
+}
@@ -1,27 +1,478 @@
+pub use guard::DecryptedGuard;
 #[doc(hidden)] pub use include_crypt_codegen as codegen;
 #[doc(hidden)] pub use include_crypt_crypto as crypto;
+#[cfg(all(feature = "secure-memory", target_os = "linux"))]
+pub use locked::LockedBuffer;
 #[doc(hidden)] pub use obfstr;
+pub use solid::SolidArchive;
+pub use zeroize::Zeroizing;
+
+pub use crypto::{
+    keyring::Keyring,
+    provider::{EnvProvider, FileProvider, KeyProvider},
+    signature::PublicKey,
+};
+
+#[cfg(unix)] pub use crypto::provider::AgentProvider;
 
 use crate::obfstr::{random, ObfString};
 use crypto::{
-    aes::{aes_decrypt, AES_KEY_LEN, AES_NONCE_LEN},
-    key::EncryptionKey,
-    xor::{xor, XOR_KEY_LEN},
+    aes::{Aes, AES_KEY_LEN, AES_NONCE_LEN},
+    aes_gcm::{AesGcm, AES_GCM_KEY_LEN, AES_GCM_NONCE_LEN, AES_GCM_TAG_LEN},
+    chacha::{ChaCha, CHACHA_KEY_LEN, CHACHA_NONCE_LEN, CHACHA_TAG_LEN},
+    cipher::{Cipher, CipherError},
+    compression::Algorithm,
+    envelope::{unwrap_key, MASTER_KEY_LEN},
+    kdf::{derive_file_key, Kdf, FOLDER_KEY_LEN, KDF_SALT_LEN},
+    key::{EncryptionKey, KeyError},
+    mac::{hmac_verify, MAC_KEY_LEN, MAC_TAG_LEN},
+    signature::{ed25519_manifest, ed25519_verify, ED25519_SIGNATURE_LEN},
+    x25519::{X25519, X25519_KEY_LEN, X25519_TAG_LEN},
+    xor::{Xor, XorStream, XOR_KEY_LEN, XOR_NONCE_LEN},
 };
 use std::{
     collections::hash_map::DefaultHasher,
+    convert::TryFrom,
+    fmt,
     hash::{Hash, Hasher},
     string::FromUtf8Error,
 };
+use zeroize::Zeroize;
+
+mod guard;
+#[cfg(all(feature = "secure-memory", target_os = "linux"))] mod locked;
+mod solid;
+
+/// The errors that can occur while decrypting an embedded file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecryptError {
+    /// The embedded key or nonce is not a valid hex string.
+    KeyFormat,
+
+    /// The embedded key, nonce or tag doesn't have the length the cipher
+    /// expects.
+    KeyLength,
+
+    /// The key couldn't be derived from the password, because the embedded
+    /// parameters are invalid.
+    KeyDerivation,
+
+    /// The authentication tag didn't match the embedded buffer. This means
+    /// that the buffer has been modified after it has been encrypted.
+    Integrity,
+
+    /// The decrypted buffer couldn't be decompressed, e.g. because the feature
+    /// of its compression algorithm isn't enabled.
+    Decompression,
+
+    /// The decrypted buffer is not a valid utf-8 string.
+    Utf8(FromUtf8Error),
+
+    /// The key is not embedded and the key provider has no key for the key
+    /// id.
+    MissingKey,
+
+    /// The keyring has no key for the key id of the file.
+    UnknownKeyId(&'static str),
+
+    /// The embedded buffer hasn't been signed or the signature doesn't match
+    /// the public key.
+    Signature,
+
+    /// The mac didn't match the embedded buffer. This means that the buffer
+    /// has been patched or corrupted after it has been encrypted.
+    Mac,
+
+    /// The locked memory for the decrypted buffer couldn't be allocated. This
+    /// is only returned by `EncryptedFile::decrypt_locked`.
+    Memory,
+
+    /// The index of the solid archive is invalid or doesn't contain the file.
+    Archive,
+}
+
+impl fmt::Display for DecryptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecryptError::KeyFormat => write!(f, "the embedded key is not a valid hex string"),
+            DecryptError::KeyLength => write!(f, "the embedded key, nonce or tag has an invalid length"),
+            DecryptError::KeyDerivation => write!(f, "the key couldn't be derived from the password"),
+            DecryptError::Integrity => write!(f, "the authentication tag of the embedded buffer didn't match"),
+            DecryptError::Decompression => write!(f, "the embedded buffer couldn't be decompressed"),
+            DecryptError::Utf8(e) => write!(f, "the embedded buffer is not valid utf-8: {}", e),
+            DecryptError::MissingKey => write!(f, "the key provider has no key for the key id"),
+            DecryptError::UnknownKeyId(key_id) => write!(f, "the keyring has no key for the key id {:?}", key_id),
+            DecryptError::Signature => write!(f, "the signature of the embedded buffer is invalid"),
+            DecryptError::Mac => write!(f, "the mac of the embedded buffer didn't match"),
+            DecryptError::Memory => write!(f, "the locked memory couldn't be allocated"),
+            DecryptError::Archive => write!(f, "the solid archive doesn't contain the file"),
+        }
+    }
+}
+
+impl std::error::Error for DecryptError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DecryptError::Utf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<KeyError> for DecryptError {
+    fn from(error: KeyError) -> Self {
+        match error {
+            KeyError::Format => DecryptError::KeyFormat,
+            KeyError::Length => DecryptError::KeyLength,
+            KeyError::Derivation => DecryptError::KeyDerivation,
+        }
+    }
+}
+
+impl From<CipherError> for DecryptError {
+    fn from(error: CipherError) -> Self {
+        match error {
+            CipherError::InvalidLength => DecryptError::KeyLength,
+            CipherError::Integrity => DecryptError::Integrity,
+        }
+    }
+}
+
+impl From<FromUtf8Error> for DecryptError {
+    fn from(error: FromUtf8Error) -> Self { DecryptError::Utf8(error) }
+}
+
+/// An obfuscated key that is embedded into the binary. This is implemented for
+/// the [`SplitKey`]s that are generated by the proc macros, so that keys of
+/// custom ciphers can have an arbitrary size.
+pub trait ObfKey: Sync {
+    /// Deobfuscates the key and decodes it.
+    ///
+    /// # Parameters
+    ///
+    /// - `key_len`: The length of the key in bytes.
+    fn deobfuscate_key(&self, key_len: usize) -> Result<EncryptionKey, KeyError>;
+}
+
+/// A key that has been split into several shares. Each share is stored in a
+/// separate static and the shares are combined with arithmetic that is
+/// generated randomly for every call site. Neither the key nor a single
+/// pattern that finds all the shares is stored in the binary.
+pub struct SplitKey<const LEN: usize> {
+    combine: fn(&mut [u8; LEN]),
+}
+
+impl<const LEN: usize> SplitKey<LEN> {
+    /// Creates a new key from the function that combines the shares.
+    ///
+    /// # Parameters
+    ///
+    /// - `combine`: The function that writes the combined shares into the
+    ///   buffer. It is generated by the proc macros.
+    pub const fn new(combine: fn(&mut [u8; LEN])) -> Self { Self { combine } }
+}
+
+impl<const LEN: usize> ObfKey for SplitKey<LEN> {
+    #[inline(always)]
+    fn deobfuscate_key(&self, key_len: usize) -> Result<EncryptionKey, KeyError> {
+        // Combine the shares straight into the heap buffer, which is moved into
+        // the key without copying it.
+        //
+        let mut key = Zeroizing::new(vec![0u8; LEN]);
+        if let Ok(buffer) = <&mut [u8; LEN]>::try_from(key.as_mut_slice()) {
+            (self.combine)(buffer);
+        }
+
+        EncryptionKey::from(take(&mut key)).with_len(key_len)
+    }
+}
+
+/// Hex encoded keys can be embedded with `obfstr::obfconst!`.
+impl<const LEN: usize> ObfKey for ObfString<[u8; LEN]> {
+    #[inline(always)]
+    fn deobfuscate_key(&self, key_len: usize) -> Result<EncryptionKey, KeyError> {
+        let mut key = self.deobfuscate(random!(u16) as usize);
+        let result = EncryptionKey::new(key.as_str(), key_len);
+        key.0.zeroize();
+
+        result
+    }
+}
+
+/// Public keys and nonces don't have to be obfuscated.
+impl<const LEN: usize> ObfKey for [u8; LEN] {
+    #[inline(always)]
+    fn deobfuscate_key(&self, key_len: usize) -> Result<EncryptionKey, KeyError> {
+        EncryptionKey::from(self.to_vec()).with_len(key_len)
+    }
+}
+
+/// The data key of an envelope, wrapped by the master key. Rotating the master
+/// key only rewraps these bytes (see `include_crypt::crypto::envelope`).
+pub enum WrappedKey {
+    /// The data key wrapped by the master key, which has been read from a file
+    /// at compile time and is embedded.
+    Embedded(SplitKey<MASTER_KEY_LEN>, &'static [u8]),
+
+    /// The data key wrapped by the master key with the key id. The master key
+    /// is not embedded and has to be supplied by a [`KeyProvider`].
+    External(&'static str, &'static [u8]),
+}
+
+impl WrappedKey {
+    /// Returns the id of the master key if it has to be supplied at runtime.
+    #[inline(always)]
+    pub fn key_id(&self) -> Option<&'static str> {
+        match self {
+            WrappedKey::External(key_id, _) => Some(key_id),
+            WrappedKey::Embedded(..) => None,
+        }
+    }
+
+    /// Unwraps the data key with the embedded master key or the master key
+    /// from the key provider.
+    ///
+    /// # Returns
+    ///
+    /// If the master key is not embedded and the provider has no key for the
+    /// key id, `Err(DecryptError::MissingKey)` will be returned. If the master
+    /// key is wrong, `Err(DecryptError::Integrity)` will be returned.
+    #[inline(always)]
+    fn unwrap_data_key(&self, provider: Option<&dyn KeyProvider>) -> Result<EncryptionKey, DecryptError> {
+        let master_key = match self {
+            WrappedKey::Embedded(key, _) => key.deobfuscate_key(MASTER_KEY_LEN)?,
+            WrappedKey::External(key_id, _) => provider
+                .and_then(|provider| provider.key(key_id))
+                .ok_or(DecryptError::MissingKey)?,
+        };
+
+        self.unwrap_with(master_key)
+    }
+
+    /// Unwraps the data key with the specified master key. The master key is
+    /// repeated or truncated to the length of the master key.
+    #[inline(always)]
+    fn unwrap_with(&self, master_key: EncryptionKey) -> Result<EncryptionKey, DecryptError> {
+        let wrapped = match self {
+            WrappedKey::Embedded(_, wrapped) | WrappedKey::External(_, wrapped) => wrapped,
+        };
+
+        Ok(unwrap_key(wrapped, &master_key.with_len(MASTER_KEY_LEN)?)?)
+    }
+}
+
+/// Unwraps the data key with the first recipient whose wrapped key can be
+/// unwrapped with the master key that is returned for it.
+///
+/// # Returns
+///
+/// If there's no master key for any of the recipients,
+/// `Err(DecryptError::MissingKey)` will be returned. If none of the master
+/// keys matches, `Err(DecryptError::Integrity)` will be returned.
+#[inline(always)]
+fn unwrap_recipients<F: Fn(&WrappedKey) -> Option<EncryptionKey>>(
+    recipients: &[WrappedKey], master_key: F,
+) -> Result<EncryptionKey, DecryptError> {
+    let mut error = DecryptError::MissingKey;
+    for recipient in recipients {
+        if let Some(master_key) = master_key(recipient) {
+            match recipient.unwrap_with(master_key) {
+                Ok(data_key) => return Ok(data_key),
+                Err(e) => error = e,
+            }
+        }
+    }
 
-/// The different encryption types with their encryption keys. The obfuscated
-/// strings have double the size because of the hex encoding.
+    Err(error)
+}
+
+/// The different encryption types with their encryption keys.
 pub enum EncryptionType {
     /// The xor encryption type with the key.
-    Xor(ObfString<[u8; XOR_KEY_LEN * 2]>),
+    Xor(SplitKey<XOR_KEY_LEN>),
+
+    /// The keystream xor encryption type with the key and nonce.
+    XorStream(SplitKey<XOR_KEY_LEN>, SplitKey<XOR_NONCE_LEN>),
 
     /// The aes encryption type with the key and nonce.
-    Aes(ObfString<[u8; AES_KEY_LEN * 2]>, ObfString<[u8; AES_NONCE_LEN * 2]>),
+    Aes(SplitKey<AES_KEY_LEN>, SplitKey<AES_NONCE_LEN>),
+
+    /// The authenticated aes gcm encryption type with the key, nonce and
+    /// authentication tag.
+    AesGcm(
+        SplitKey<AES_GCM_KEY_LEN>,
+        SplitKey<AES_GCM_NONCE_LEN>,
+        &'static [u8; AES_GCM_TAG_LEN],
+    ),
+
+    /// The authenticated xchacha20-poly1305 encryption type with the key,
+    /// nonce and authentication tag.
+    ChaCha(
+        SplitKey<CHACHA_KEY_LEN>,
+        SplitKey<CHACHA_NONCE_LEN>,
+        &'static [u8; CHACHA_TAG_LEN],
+    ),
+
+    /// A custom cipher with the key, nonce and authentication tag. The buffer
+    /// has been sealed ahead of time, so it will never be compressed.
+    Custom(
+        &'static dyn Cipher,
+        &'static dyn ObfKey,
+        &'static dyn ObfKey,
+        &'static [u8],
+    ),
+
+    /// The authenticated aes gcm encryption type with the key derivation
+    /// function, salt, nonce and authentication tag. The key is derived from
+    /// the password of the user.
+    Password(
+        Kdf,
+        &'static [u8; KDF_SALT_LEN],
+        SplitKey<AES_GCM_NONCE_LEN>,
+        &'static [u8; AES_GCM_TAG_LEN],
+    ),
+
+    /// The x25519 sealed box with the ephemeral public key and authentication
+    /// tag. The file can only be decrypted with the secret key of the
+    /// recipient.
+    X25519(&'static [u8; X25519_KEY_LEN], &'static [u8; X25519_TAG_LEN]),
+
+    /// A built-in cipher with the key id, nonce and authentication tag. The
+    /// key is not embedded and has to be supplied by a [`KeyProvider`].
+    External(&'static dyn Cipher, &'static str, &'static dyn ObfKey, &'static [u8]),
+
+    /// Several built-in encryption types which have been applied in order,
+    /// each with its own key. The layers will be decrypted in reverse order.
+    Cascade(&'static [EncryptionType]),
+
+    /// A built-in cipher with the wrapped data key, nonce and authentication
+    /// tag. The data key is unwrapped with the master key before the buffer is
+    /// decrypted.
+    Envelope(
+        &'static dyn Cipher,
+        &'static WrappedKey,
+        &'static dyn ObfKey,
+        &'static [u8],
+    ),
+
+    /// A built-in cipher of a folder with the folder key, the obfuscated
+    /// relative path of the file and its length, and the authentication tag.
+    /// The key and nonce of the file are derived from the folder key and the
+    /// `/` separated relative path when the file is decrypted, so that only
+    /// the folder key is embedded.
+    Derived(
+        &'static dyn Cipher,
+        &'static SplitKey<FOLDER_KEY_LEN>,
+        &'static dyn ObfKey,
+        usize,
+        &'static [u8],
+    ),
+
+    /// A built-in cipher with the table of recipients, nonce and
+    /// authentication tag. The data key has been wrapped once per recipient,
+    /// so that the key of any recipient can unwrap it.
+    Recipients(
+        &'static dyn Cipher,
+        &'static [WrappedKey],
+        &'static dyn ObfKey,
+        &'static [u8],
+    ),
+
+    /// A file of a solid archive with the id of the file. The archive is
+    /// decrypted once and the file is a range of it, so that no key is
+    /// embedded for the file.
+    Solid(&'static SolidArchive, &'static str),
+}
+
+/// The cipher, the embedded key, the optional nonce and the authentication tag
+/// of an encryption type.
+type Parts<'a> = (&'a dyn Cipher, Option<&'a dyn ObfKey>, Option<&'a dyn ObfKey>, &'a [u8]);
+
+impl EncryptionType {
+    /// Returns the cipher, the embedded key, the optional nonce and the
+    /// authentication tag, so that all encryption types can be decrypted the
+    /// same way. The key is `None` if it has to be supplied at runtime.
+    /// Cascades don't have a single cipher and the nonces of derived files
+    /// aren't embedded, so `None` will be returned for them.
+    #[inline(always)]
+    fn parts(&self) -> Option<Parts<'_>> {
+        Some(match self {
+            EncryptionType::Xor(key) => (&Xor, Some(key), None, &[]),
+            EncryptionType::XorStream(key, nonce) => (&XorStream, Some(key), Some(nonce), &[]),
+            EncryptionType::Aes(key, nonce) => (&Aes, Some(key), Some(nonce), &[]),
+            EncryptionType::AesGcm(key, nonce, tag) => (&AesGcm, Some(key), Some(nonce), &tag[..]),
+            EncryptionType::ChaCha(key, nonce, tag) => (&ChaCha, Some(key), Some(nonce), &tag[..]),
+            EncryptionType::Custom(cipher, key, nonce, tag) => (*cipher, Some(*key), Some(*nonce), tag),
+            EncryptionType::Password(_, _, nonce, tag) => (&AesGcm, None, Some(nonce), &tag[..]),
+            EncryptionType::X25519(ephemeral, tag) => (&X25519, None, Some(*ephemeral), &tag[..]),
+            EncryptionType::External(cipher, _, nonce, tag) => (*cipher, None, Some(*nonce), tag),
+            EncryptionType::Envelope(cipher, _, nonce, tag) => (*cipher, None, Some(*nonce), tag),
+            EncryptionType::Recipients(cipher, _, nonce, tag) => (*cipher, None, Some(*nonce), tag),
+            EncryptionType::Cascade(_) | EncryptionType::Derived(..) | EncryptionType::Solid(..) => return None,
+        })
+    }
+
+    /// Decrypts the buffer in place. If no key is specified, the embedded key
+    /// or the data key that is unwrapped with the embedded master key will be
+    /// used. The layers of a cascade are decrypted in reverse order
+    /// with their embedded keys.
+    #[inline(always)]
+    fn decrypt(&self, buffer: &mut [u8], key: Option<EncryptionKey>) -> Result<(), DecryptError> {
+        let (cipher, embedded_key, nonce, tag) = match self {
+            EncryptionType::Cascade(layers) => {
+                return layers.iter().rev().try_for_each(|layer| layer.decrypt(buffer, None));
+            }
+            EncryptionType::Derived(cipher, folder_key, path, path_len, tag) => {
+                let folder_key = folder_key.deobfuscate_key(FOLDER_KEY_LEN)?;
+                let path = path.deobfuscate_key(*path_len)?;
+                let path = std::str::from_utf8(&path).map_err(|_| KeyError::Format)?;
+                let (key, nonce) = derive_file_key(&folder_key, path, cipher.key_len(), cipher.nonce_len())?;
+
+                cipher.decrypt(buffer, &key, &nonce, tag)?;
+                return Ok(());
+            }
+            _ => self.parts().ok_or(DecryptError::MissingKey)?,
+        };
+
+        // Using `?` instead of `unwrap` also gets rid of the panic strings in the
+        // binary.
+        //
+        let key = match (key, self) {
+            (Some(key), _) => key,
+            (None, EncryptionType::Envelope(_, wrapped, _, _)) => wrapped.unwrap_data_key(None)?,
+            (None, _) => embedded_key
+                .ok_or(DecryptError::MissingKey)?
+                .deobfuscate_key(cipher.key_len())?,
+        };
+        let nonce = match nonce {
+            Some(nonce) => nonce.deobfuscate_key(cipher.nonce_len())?,
+            None => EncryptionKey::from(Vec::new()),
+        };
+
+        cipher.decrypt(buffer, &key, &nonce, tag)?;
+        Ok(())
+    }
+
+    /// Returns the id of the key if it has to be supplied at runtime. For
+    /// envelopes this is the id of the master key.
+    #[inline(always)]
+    pub fn key_id(&self) -> Option<&'static str> {
+        match self {
+            EncryptionType::External(_, key_id, _, _) => Some(key_id),
+            _ => self.wrapped_key().and_then(WrappedKey::key_id),
+        }
+    }
+
+    /// Returns the wrapped data key of an envelope. The files of a solid
+    /// archive return the data key of the archive.
+    #[inline(always)]
+    fn wrapped_key(&self) -> Option<&'static WrappedKey> {
+        match self {
+            EncryptionType::Envelope(_, wrapped, _, _) => Some(wrapped),
+            EncryptionType::Solid(archive, _) => archive.file().enc_type.wrapped_key(),
+            _ => None,
+        }
+    }
 }
 
 /// The structure which is used to store the encrypted buffer and the decryption
@@ -32,6 +483,16 @@ pub struct EncryptedFile {
 
     /// The type of the encryption that has been used.
     enc_type: EncryptionType,
+
+    /// The optional signature of the plaintext and the public key of the
+    /// signer.
+    signature: Option<(&'static [u8; ED25519_SIGNATURE_LEN], PublicKey)>,
+
+    /// The optional key and mac of the encrypted buffer.
+    mac: Option<(SplitKey<MAC_KEY_LEN>, &'static [u8; MAC_TAG_LEN])>,
+
+    /// The optional zstd dictionary which is shared by the files of a folder.
+    dictionary: Option<&'static EncryptedFile>,
 }
 
 impl EncryptedFile {
@@ -42,7 +503,9 @@ impl EncryptedFile {
     /// # Parameters
     ///
     /// - `buffer`: The buffer with the encrypted bytes. This will be the output
-    ///   of the `encrypt_xor` / `encrypt_aes` proc macros.
+    ///   of the `encrypt_xor` / `encrypt_xor_stream` / `encrypt_aes` /
+    ///   `encrypt_aes_gcm` / `encrypt_chacha` / `encrypt_with` /
+    ///   `encrypt_cascade` proc macros.
     /// - `enc_type`: The type of the encryption. This will be used to decrypt
     ///   the buffer as it also stores the decryption keys for the different
     ///   algorithms. If the key is randomly generated it will also be returned
@@ -52,75 +515,573 @@ impl EncryptedFile {
     ///
     /// Returns a `EncryptedFile` instance which can be used to decrypt the
     /// internal buffer.
-    pub const fn new(buffer: &'static [u8], enc_type: EncryptionType) -> Self { Self { buffer, enc_type } }
+    pub const fn new(buffer: &'static [u8], enc_type: EncryptionType) -> Self {
+        Self {
+            buffer,
+            enc_type,
+            signature: None,
+            mac: None,
+            dictionary: None,
+        }
+    }
+
+    /// Adds the signature of the plaintext.
+    ///
+    /// # Parameters
+    ///
+    /// - `signature`: The ed25519 signature of the plaintext. This will be the
+    ///   output of the `sign_file` proc macro.
+    /// - `public_key`: The public key of the signer. It is only embedded for
+    ///   informational purposes, the signature has to be verified with a
+    ///   trusted public key.
+    pub const fn with_signature(self, signature: &'static [u8; ED25519_SIGNATURE_LEN], public_key: PublicKey) -> Self {
+        Self {
+            signature: Some((signature, public_key)),
+            ..self
+        }
+    }
+
+    /// Adds the mac of the encrypted buffer. The mac will be verified before
+    /// the buffer is decrypted, so that a patched or corrupted buffer is
+    /// detected for every encryption type.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: The obfuscated key of the mac.
+    /// - `mac`: The HMAC-SHA256 of the encrypted buffer. This will be computed
+    ///   by the proc macros if `mac` is specified.
+    pub const fn with_mac(self, key: SplitKey<MAC_KEY_LEN>, mac: &'static [u8; MAC_TAG_LEN]) -> Self {
+        Self {
+            mac: Some((key, mac)),
+            ..self
+        }
+    }
+
+    /// Adds the zstd dictionary which the file has been compressed with. The
+    /// dictionary is encrypted like the files of the folder and decrypted
+    /// with the same key when the file is decompressed.
+    ///
+    /// # Parameters
+    ///
+    /// - `dictionary`: The encrypted dictionary. This will be generated by the
+    ///   `include_files` proc macro if `compress = zstd_dict` is specified.
+    pub const fn with_dictionary(self, dictionary: &'static EncryptedFile) -> Self {
+        Self {
+            dictionary: Some(dictionary),
+            ..self
+        }
+    }
+
+    /// Decrypts the internal buffer and returns it.
+    ///
+    /// # Returns
+    ///
+    /// Returns the decrypted buffer.
+    ///
+    /// # Aborts
+    ///
+    /// Aborts the process if the buffer couldn't be decrypted, e.g. because
+    /// the integrity of an authenticated buffer couldn't be verified or the
+    /// key has to be supplied at runtime. Use [`EncryptedFile::try_decrypt`]
+    /// to handle these cases.
+    #[inline(always)]
+    pub fn decrypt(&self) -> Vec<u8> {
+        // By aborting instead of panicking we are getting rid of the panic strings
+        // in the binary.
+        //
+        self.try_decrypt().unwrap_or_else(|_| std::process::abort())
+    }
+
+    /// Decrypts the internal buffer and returns it.
+    ///
+    /// # Returns
+    ///
+    /// If the embedded key is invalid, the authentication tag of an
+    /// authenticated encryption type doesn't match or the buffer couldn't be
+    /// decompressed, the corresponding [`DecryptError`] will be returned. If
+    /// the key is not embedded, `Err(DecryptError::MissingKey)` will be
+    /// returned. Otherwise the decrypted buffer will be returned.
+    #[inline(always)]
+    pub fn try_decrypt(&self) -> Result<Vec<u8>, DecryptError> { self.decrypt_secret().map(|mut data| take(&mut data)) }
+
+    /// Decrypts the internal buffer and returns it in a wrapper which
+    /// overwrites the decrypted bytes with zeros when it is dropped. The
+    /// intermediate buffers, e.g. of the decompression, are wiped as well.
+    ///
+    /// # Returns
+    ///
+    /// See [`EncryptedFile::try_decrypt`].
+    #[inline(always)]
+    pub fn decrypt_secret(&self) -> Result<Zeroizing<Vec<u8>>, DecryptError> { self.decrypt_key(None) }
+
+    /// Decrypts the internal buffer and returns a guard which wipes the
+    /// decrypted bytes when it is dropped.
+    ///
+    /// # Returns
+    ///
+    /// See [`EncryptedFile::try_decrypt`].
+    #[inline(always)]
+    pub fn decrypt_guard(&self) -> Result<DecryptedGuard, DecryptError> { self.decrypt_secret().map(Into::into) }
+
+    /// Decrypts the internal buffer and passes it to the closure. The
+    /// decrypted bytes will be wiped as soon as the closure returns.
+    ///
+    /// # Parameters
+    ///
+    /// - `f`: The closure which receives the decrypted buffer.
+    ///
+    /// # Returns
+    ///
+    /// Returns the result of the closure. If the buffer couldn't be decrypted,
+    /// the closure won't be called and the error will be returned (see
+    /// [`EncryptedFile::try_decrypt`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use include_crypt::{include_crypt, EncryptedFile};
+    /// let file: EncryptedFile = include_crypt!(AES_GCM, "src/lib.rs");
+    ///
+    /// let len = file.with_decrypted(|bytes| bytes.len()).unwrap();
+    /// ```
+    #[inline(always)]
+    pub fn with_decrypted<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> Result<R, DecryptError> {
+        self.decrypt_guard().map(|guard| f(&guard))
+    }
+
+    /// Decrypts the internal buffer into memory that is excluded from core
+    /// dumps, locked into memory and read-only. The file is decrypted and
+    /// decompressed straight into the locked pages, only the zstd dictionary
+    /// and solid archives are decrypted on the heap. The pages will be wiped
+    /// and unmapped when the buffer is dropped.
+    ///
+    /// # Returns
+    ///
+    /// If the memory couldn't be mapped, `Err(DecryptError::Memory)` will be
+    /// returned. If the memory couldn't be locked because `RLIMIT_MEMLOCK` is
+    /// too small, the buffer will still be returned, but
+    /// [`LockedBuffer::is_locked`] will be `false`. Otherwise see
+    /// [`EncryptedFile::try_decrypt`].
+    #[cfg(all(feature = "secure-memory", target_os = "linux"))]
+    #[inline(always)]
+    pub fn decrypt_locked(&self) -> Result<LockedBuffer, DecryptError> {
+        self.decrypt_into::<LockedBuffer>(None)?
+            .protect()
+            .ok_or(DecryptError::Memory)
+    }
+
+    /// Decrypts the internal buffer with a key from the key provider and
+    /// returns it. Files with an embedded key ignore the key provider. For
+    /// envelopes, the provider supplies the master key which unwraps the data
+    /// key. For files with several recipients, the key of every recipient is
+    /// requested until one of them unwraps the data key.
+    ///
+    /// # Parameters
+    ///
+    /// - `provider`: The provider that supplies the key for the key id of the
+    ///   file.
+    ///
+    /// # Returns
+    ///
+    /// If the provider has no key for the key id,
+    /// `Err(DecryptError::MissingKey)` will be returned. Otherwise see
+    /// [`EncryptedFile::try_decrypt`].
+    #[inline(always)]
+    pub fn decrypt_with(&self, provider: &dyn KeyProvider) -> Result<Vec<u8>, DecryptError> {
+        if let EncryptionType::Recipients(_, recipients, _, _) = self.enc_type {
+            let key = unwrap_recipients(recipients, |recipient| {
+                recipient.key_id().and_then(|key_id| provider.key(key_id))
+            })?;
+
+            return self.decrypt_key(Some(key)).map(|mut data| take(&mut data));
+        }
+
+        let key_id = match self.enc_type.key_id() {
+            Some(key_id) => key_id,
+            None => return self.try_decrypt(),
+        };
+
+        let key = match self.enc_type.wrapped_key() {
+            Some(wrapped) => wrapped.unwrap_data_key(Some(provider))?,
+            None => {
+                let (cipher, _, _, _) = self.enc_type.parts().ok_or(DecryptError::MissingKey)?;
+                let key = provider.key(key_id).ok_or(DecryptError::MissingKey)?;
+                key.with_len(cipher.key_len())?
+            }
+        };
+        self.decrypt_key(Some(key)).map(|mut data| take(&mut data))
+    }
+
+    /// Decrypts the internal buffer with the key of its key id from the
+    /// keyring and returns it. Files with an embedded key ignore the keyring.
+    ///
+    /// # Parameters
+    ///
+    /// - `keyring`: The keyring with the current and the previous keys.
+    ///
+    /// # Returns
+    ///
+    /// If the keyring has no key for the key id,
+    /// `Err(DecryptError::UnknownKeyId)` with the key id will be returned.
+    /// Otherwise see [`EncryptedFile::try_decrypt`].
+    #[inline(always)]
+    pub fn decrypt_with_keyring(&self, keyring: &Keyring) -> Result<Vec<u8>, DecryptError> {
+        match self.enc_type.key_id() {
+            Some(key_id) if !keyring.contains(key_id) => Err(DecryptError::UnknownKeyId(key_id)),
+            _ => self.decrypt_with(keyring),
+        }
+    }
+
+    /// Decrypts the internal buffer with the key of one of its recipients and
+    /// returns it. The key is tried against the wrapped key of every
+    /// recipient, so the key id doesn't have to be known. Files without
+    /// recipients ignore it.
+    ///
+    /// # Parameters
+    ///
+    /// - `key`: The key of the recipient, e.g. the license key of a customer.
+    ///
+    /// # Returns
+    ///
+    /// If the key doesn't belong to any of the recipients,
+    /// `Err(DecryptError::Integrity)` will be returned. Otherwise see
+    /// [`EncryptedFile::try_decrypt`].
+    #[inline(always)]
+    pub fn decrypt_with_key(&self, key: &EncryptionKey) -> Result<Vec<u8>, DecryptError> {
+        let recipients = match self.enc_type {
+            EncryptionType::Recipients(_, recipients, _, _) => recipients,
+            _ => return self.try_decrypt(),
+        };
+
+        let key = unwrap_recipients(recipients, |_| Some(EncryptionKey::from(key.to_vec())))?;
+        self.decrypt_key(Some(key)).map(|mut data| take(&mut data))
+    }
+
+    /// Returns the key ids of the recipients if the data key has been wrapped
+    /// for several recipients.
+    #[inline(always)]
+    pub fn recipients(&self) -> Option<impl Iterator<Item = &'static str>> {
+        match self.enc_type {
+            EncryptionType::Recipients(_, recipients, _, _) => Some(recipients.iter().filter_map(WrappedKey::key_id)),
+            _ => None,
+        }
+    }
+
+    /// Decrypts the internal buffer with a key that is derived from the
+    /// password and returns it. Files that haven't been encrypted with a
+    /// password ignore it.
+    ///
+    /// # Parameters
+    ///
+    /// - `password`: The password that has been used to encrypt the file.
+    ///
+    /// # Returns
+    ///
+    /// If the password is wrong, the authentication tag doesn't match and
+    /// `Err(DecryptError::Integrity)` will be returned. Otherwise see
+    /// [`EncryptedFile::try_decrypt`].
+    #[inline(always)]
+    pub fn decrypt_with_password(&self, password: &str) -> Result<Vec<u8>, DecryptError> {
+        let (kdf, salt) = match &self.enc_type {
+            EncryptionType::Password(kdf, salt, _, _) => (kdf, salt),
+            _ => return self.try_decrypt(),
+        };
+
+        let key = kdf.derive(password.as_bytes(), &salt[..], AES_GCM_KEY_LEN)?;
+        self.decrypt_key(Some(key)).map(|mut data| take(&mut data))
+    }
+
+    /// Decrypts the internal buffer with the x25519 secret key of the recipient
+    /// and returns it. Files that haven't been encrypted to a public key
+    /// ignore it.
+    ///
+    /// # Parameters
+    ///
+    /// - `secret_key`: The secret key of the recipient. It must be exactly 32
+    ///   bytes.
+    ///
+    /// # Returns
+    ///
+    /// If the secret key is wrong, the authentication tag doesn't match and
+    /// `Err(DecryptError::Integrity)` will be returned. Otherwise see
+    /// [`EncryptedFile::try_decrypt`].
+    #[inline(always)]
+    pub fn decrypt_with_secret_key(&self, secret_key: &[u8]) -> Result<Vec<u8>, DecryptError> {
+        match self.enc_type {
+            EncryptionType::X25519(..) => self
+                .decrypt_key(Some(EncryptionKey::from(secret_key.to_vec())))
+                .map(|mut data| take(&mut data)),
+            _ => self.try_decrypt(),
+        }
+    }
+
+    /// Decrypts the internal buffer and verifies the signature of the
+    /// plaintext.
+    ///
+    /// # Parameters
+    ///
+    /// - `public_key`: The trusted public key of the signer.
+    ///
+    /// # Returns
+    ///
+    /// If the file hasn't been signed or the signature doesn't match,
+    /// `Err(DecryptError::Signature)` will be returned. Otherwise see
+    /// [`EncryptedFile::try_decrypt`].
+    #[inline(always)]
+    pub fn verify_and_decrypt(&self, public_key: &PublicKey) -> Result<Vec<u8>, DecryptError> {
+        let data = self.try_decrypt()?;
+        self.verify(&data, public_key)?;
+
+        Ok(data)
+    }
+
+    /// Verifies the signature of the decrypted buffer. This can be used for
+    /// files which have to be decrypted with a key that is supplied at runtime.
+    ///
+    /// # Parameters
+    ///
+    /// - `data`: The decrypted buffer.
+    /// - `public_key`: The trusted public key of the signer.
+    ///
+    /// # Returns
+    ///
+    /// If the file hasn't been signed or the signature doesn't match,
+    /// `Err(DecryptError::Signature)` will be returned.
+    #[inline(always)]
+    pub fn verify(&self, data: &[u8], public_key: &PublicKey) -> Result<(), DecryptError> {
+        match self.signature {
+            Some((signature, _)) if ed25519_verify(data, public_key, &signature[..]) => Ok(()),
+            _ => Err(DecryptError::Signature),
+        }
+    }
+
+    /// Returns the embedded public key of the signer if the file has been
+    /// signed.
+    #[inline(always)]
+    pub fn public_key(&self) -> Option<&PublicKey> { self.signature.as_ref().map(|(_, public_key)| public_key) }
+
+    /// Returns the content of a file of a solid archive without copying it.
+    /// The archive is decrypted with the embedded key when the first file is
+    /// accessed and the slice borrows from it for the rest of the program.
+    ///
+    /// # Returns
+    ///
+    /// If the file isn't part of a solid archive, `None` will be returned. If
+    /// the master key of the archive has to be supplied at runtime,
+    /// `Err(DecryptError::MissingKey)` will be returned (see
+    /// [`UnwrappedFolder::decrypt_slice`]). Otherwise see
+    /// [`EncryptedFile::try_decrypt`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use include_crypt::{include_dir, EncryptedFolder};
+    /// let folder: EncryptedFolder = include_dir!(AES, "./src", solid);
+    ///
+    /// let file = folder.get("lib.rs").unwrap();
+    /// assert!(file.decrypt_slice().unwrap().is_ok());
+    /// ```
+    #[inline(always)]
+    pub fn decrypt_slice(&self) -> Option<Result<&'static [u8], DecryptError>> {
+        match self.enc_type {
+            EncryptionType::Solid(archive, file_id) => Some(archive.get(file_id, None)),
+            _ => None,
+        }
+    }
+
+    /// Returns the id of the key if it has to be supplied by a [`KeyProvider`].
+    #[inline(always)]
+    pub fn key_id(&self) -> Option<&'static str> { self.enc_type.key_id() }
+
+    /// Decrypts and decompresses the internal buffer with the specified key.
+    /// If no key is specified, the embedded key will be used. The key and all
+    /// the intermediate buffers will be wiped.
+    #[inline(always)]
+    fn decrypt_key(&self, key: Option<EncryptionKey>) -> Result<Zeroizing<Vec<u8>>, DecryptError> {
+        self.decrypt_into(key)
+    }
+
+    /// Decrypts and decompresses the internal buffer into the plaintext buffer
+    /// (see [`EncryptedFile::decrypt_key`]).
+    #[inline(always)]
+    fn decrypt_into<P: Plaintext>(&self, key: Option<EncryptionKey>) -> Result<P, DecryptError> {
+        // The files of a solid archive are copied out of the decrypted archive.
+        //
+        if let EncryptionType::Solid(archive, file_id) = self.enc_type {
+            let data = archive.get(file_id, key)?;
+            let mut buffer = P::with_capacity(data.len())?;
+            buffer.append(data);
+
+            return Ok(buffer);
+        }
+
+        if let Some((mac_key, mac)) = &self.mac {
+            if !hmac_verify(self.buffer, &mac_key.deobfuscate_key(MAC_KEY_LEN)?, &mac[..]) {
+                return Err(DecryptError::Mac);
+            }
+        }
+
+        // The dictionary is encrypted with the same key as the file.
+        //
+        let dictionary_key = match (self.dictionary, &key) {
+            (Some(_), Some(key)) => Some(EncryptionKey::from(key.to_vec())),
+            _ => None,
+        };
+
+        let mut buffer = P::with_capacity(self.buffer.len())?;
+        buffer.append(self.buffer);
+        self.enc_type.decrypt(buffer.as_mut_slice(), key)?;
+
+        // Sealed buffers of custom ciphers are embedded as they are.
+        //
+        if let EncryptionType::Custom(..) = self.enc_type {
+            return Ok(buffer);
+        }
+
+        decompress(buffer, || match self.dictionary {
+            Some(dictionary) => dictionary.decrypt_key(dictionary_key),
+            None => Err(DecryptError::Decompression),
+        })
+    }
+
+    /// Decrypts the internal buffer and returns it as a string.
+    ///
+    /// # Returns
+    ///
+    /// If the decrypted buffer is not a valid utf-8 string, an error will be
+    /// returned. If it is a valid utf-8 string, it will be returned.
+    ///
+    /// # Aborts
+    ///
+    /// Aborts the process if the buffer couldn't be decrypted (see
+    /// [`EncryptedFile::decrypt`]).
+    #[inline(always)]
+    pub fn decrypt_str(&self) -> Result<String, FromUtf8Error> { String::from_utf8(self.decrypt()) }
+
+    /// Decrypts the internal buffer and returns it as a string.
+    ///
+    /// # Returns
+    ///
+    /// If the buffer couldn't be decrypted or is not a valid utf-8 string, the
+    /// corresponding [`DecryptError`] will be returned. Otherwise the string
+    /// will be returned.
+    #[inline(always)]
+    pub fn try_decrypt_str(&self) -> Result<String, DecryptError> { Ok(String::from_utf8(self.try_decrypt()?)?) }
+}
+
+/// Moves the bytes out of the wrapper without copying them, so that the
+/// wrapper only wipes the (now empty) allocation.
+#[inline(always)]
+fn take(data: &mut Zeroizing<Vec<u8>>) -> Vec<u8> { std::mem::take(&mut **data) }
+
+/// The buffer which a file is decrypted and decompressed into. The buffers are
+/// grown manually, because a reallocation would free the old buffer without
+/// wiping it.
+trait Plaintext: Sized {
+    /// Allocates an empty buffer for at least `capacity` bytes.
+    fn with_capacity(capacity: usize) -> Result<Self, DecryptError>;
+
+    /// Returns how many bytes can be appended without growing the buffer.
+    #[cfg_attr(
+        not(any(feature = "deflate", feature = "zstd", feature = "lz4", feature = "brotli")),
+        allow(dead_code)
+    )]
+    fn spare_capacity(&self) -> usize;
+
+    /// Appends the bytes. The buffer must have enough spare capacity.
+    fn append(&mut self, data: &[u8]);
+
+    /// Returns the content of the buffer.
+    fn as_slice(&self) -> &[u8];
 
-    /// Decrypts the internal buffer and returns it.
-    ///
-    /// # Returns
-    ///
-    /// Returns the decrypted buffer.
-    #[inline(always)]
-    pub fn decrypt(&self) -> Vec<u8> {
-        let buffer = match &self.enc_type {
-            EncryptionType::Xor(key) => {
-                let mut buffer = self.buffer.to_vec();
+    /// Returns the content of the buffer, so that it can be decrypted in
+    /// place.
+    fn as_mut_slice(&mut self) -> &mut [u8];
+
+    /// Removes the id of the compression algorithm behind the content.
+    fn without_id(self) -> Self;
+}
 
-                // By using `map` instead of `unwrap` we are getting rid of the panic strings in
-                // the binary.
-                //
-                let _ = EncryptionKey::new(key.deobfuscate(random!(u16) as usize).as_str(), XOR_KEY_LEN)
-                    .map(|key| xor(buffer.as_mut_slice(), key));
+impl Plaintext for Zeroizing<Vec<u8>> {
+    fn with_capacity(capacity: usize) -> Result<Self, DecryptError> { Ok(Zeroizing::new(Vec::with_capacity(capacity))) }
 
-                buffer.to_vec()
-            }
-            EncryptionType::Aes(key, nonce) => {
-                let mut buffer = self.buffer.to_vec();
-
-                // By using `map` instead of `unwrap` we are getting rid of the panic strings in
-                // the binary.
-                //
-                let _ = EncryptionKey::new(key.deobfuscate(random!(u16) as usize).as_str(), AES_KEY_LEN).map(|key| {
-                    EncryptionKey::new(nonce.deobfuscate(random!(u16) as usize).as_str(), AES_NONCE_LEN).map(|nonce| {
-                        // This should never fail anyways because the keys have a fixed size.
-                        //
-                        let _ = aes_decrypt(buffer.as_mut_slice(), key, nonce);
-                    })
-                });
-
-                buffer
-            }
-        };
+    fn spare_capacity(&self) -> usize { self.capacity() - self.len() }
 
-        // Decompress the file if the feature is set
-        //
-        #[cfg(feature = "compression")]
-        {
-            use std::io::Read;
+    fn append(&mut self, data: &[u8]) { self.extend_from_slice(data) }
 
-            let mut decompressed = Vec::new();
+    fn as_slice(&self) -> &[u8] { self }
 
-            let mut decoder = libflate::deflate::Decoder::new(std::io::Cursor::new(buffer));
-            decoder
-                .read_to_end(&mut decompressed)
-                .expect("The embedded deflate buffer was corrupted");
+    fn as_mut_slice(&mut self) -> &mut [u8] { self }
 
-            decompressed
-        }
+    fn without_id(mut self) -> Self {
+        self.pop();
+        self
+    }
+}
 
-        #[cfg(not(feature = "compression"))]
-        {
-            buffer
+/// Decompresses the decrypted buffer with the algorithm whose id is stored
+/// behind the compressed content, so that it can be removed without moving the
+/// content. The dictionary is only decrypted if the
+/// buffer has been compressed with it. If the decoder of the algorithm hasn't
+/// been compiled in, `Err(DecryptError::Decompression)` will be returned.
+#[cfg_attr(not(feature = "zstd"), allow(unused_variables))]
+#[inline(always)]
+fn decompress<P: Plaintext, F: FnOnce() -> Result<Zeroizing<Vec<u8>>, DecryptError>>(
+    buffer: P, dictionary: F,
+) -> Result<P, DecryptError> {
+    let (algorithm, data) = match buffer.as_slice().split_last() {
+        Some((id, data)) => (Algorithm::from_id(*id), data),
+        None => (None, &[][..]),
+    };
+
+    match algorithm {
+        Some(Algorithm::None) => Ok(buffer.without_id()),
+        #[cfg(feature = "deflate")]
+        Some(Algorithm::Deflate) => read_all(libflate::deflate::Decoder::new(data), data.len()),
+        #[cfg(feature = "zstd")]
+        Some(Algorithm::Zstd) => read_all(
+            zstd::stream::read::Decoder::with_buffer(data).map_err(|_| DecryptError::Decompression)?,
+            data.len(),
+        ),
+        #[cfg(feature = "zstd")]
+        Some(Algorithm::ZstdDictionary) => {
+            let dictionary = dictionary()?;
+            read_all(
+                zstd::stream::read::Decoder::with_dictionary(data, &dictionary)
+                    .map_err(|_| DecryptError::Decompression)?,
+                data.len(),
+            )
         }
+        #[cfg(feature = "lz4")]
+        Some(Algorithm::Lz4) => read_all(lz4_flex::frame::FrameDecoder::new(data), data.len()),
+        #[cfg(feature = "brotli")]
+        Some(Algorithm::Brotli) => read_all(brotli_decompressor::Decompressor::new(data, 4096), data.len()),
+        _ => Err(DecryptError::Decompression),
     }
+}
 
-    /// Decrypts the internal buffer and returns it as a string.
-    ///
-    /// # Returns
-    ///
-    /// If the decrypted buffer is not a valid utf-8 string, an error will be
-    /// returned. If it is a valid utf-8 string, it will be returned.
-    #[inline(always)]
-    pub fn decrypt_str(&self) -> Result<String, FromUtf8Error> { String::from_utf8(self.decrypt()) }
+/// Reads the decompressed content from the decoder. The output buffer is grown
+/// manually, because a reallocation would free the old buffer without wiping
+/// it.
+#[cfg(any(feature = "deflate", feature = "zstd", feature = "lz4", feature = "brotli"))]
+#[inline(always)]
+fn read_all<P: Plaintext, R: std::io::Read>(mut decoder: R, len: usize) -> Result<P, DecryptError> {
+    let mut decompressed = P::with_capacity(len * 2)?;
+    let mut chunk = Zeroizing::new([0u8; 4096]);
+
+    loop {
+        let len = decoder.read(&mut chunk[..]).map_err(|_| DecryptError::Decompression)?;
+        if len == 0 {
+            return Ok(decompressed);
+        }
+
+        if decompressed.spare_capacity() < len {
+            let mut grown = P::with_capacity((decompressed.as_slice().len() + len) * 2)?;
+            grown.append(decompressed.as_slice());
+            decompressed = grown;
+        }
+        decompressed.append(&chunk[..len]);
+    }
 }
 
 /// Macro that can be used to safely embed files into the binary.
@@ -131,19 +1092,71 @@ impl EncryptedFile {
 ///
 /// ```ignore
 /// include_crypt!($encryption_type, $file_path, $optional_key)
+/// include_crypt!(XOR, $file_path, $optional_key, stream)
+/// include_crypt!($encryption_type + $encryption_type + ..., $file_path)
+/// include_crypt!($encryption_type, $file_path, $optional_key, key_id = $key_id)
+/// include_crypt!($encryption_type, $file_path, key_id = $key_id, keyring = $keyring_file)
+/// include_crypt!($encryption_type, $file_path, master_key_file = $master_key_file)
+/// include_crypt!($encryption_type, $file_path, master_key_id = $key_id, keyring = $keyring_file)
+/// include_crypt!($encryption_type, $file_path, recipients = [$key_id, ...], keyring = $keyring_file)
+/// include_crypt!(PASSWORD, $file_path, env = $variable, kdf = $kdf)
+/// include_crypt!(PASSWORD, $file_path, file = $password_file, kdf = $kdf)
+/// include_crypt!(X25519, $file_path, public_key = $public_key)
+/// include_crypt!(X25519, $file_path, public_key_file = $public_key_file)
+/// include_crypt!($encryption_type, $file_path, sign = $signing_key_file, ...)
+/// include_crypt!($encryption_type, $file_path, $optional_key, mac)
+/// include_crypt!($encryption_type, $file_path, ..., compress = $algorithm($level))
 /// ```
 ///
-/// - `$encryption_type`: The type of the encryption. Either `XOR` or `AES`. If
-///   you don't specify an encryption type, `XOR` will be used.
+/// - `$encryption_type`: The type of the encryption. Either `XOR`, `AES`,
+///   `AES_GCM`, `CHACHA` or the path to a custom type that implements the
+///   `Cipher` trait. If you don't specify an encryption type, `XOR` will be
+///   used.
 /// - `$file_path`: The path to the file that should be embedded. If the path is
 ///   relative, the `CARGO_MANIFEST_DIR` will be used as a starting point.
 /// - `$optional_key`: The optional encryption key. If specified, it has to be
 ///   decodable by [hex](https://crates.io/crates/hex) crate.
+/// - `stream`: Only for `XOR`. Instead of repeating the key over the data, a
+///   keystream will be generated from the key and a random nonce.
+/// - `$encryption_type + $encryption_type + ...`: Encrypts the file with
+///   several built-in encryption types in sequence, each with its own random
+///   key. `XOR_STREAM` can be used for the keystream xor.
+/// - `$key_id`: The id of the key which will be supplied at runtime. The key
+///   will not be embedded into the binary.
+/// - `$keyring_file`: The keyring file which contains the key of the key id at
+///   compile time (see [`Keyring`]).
+/// - `[$key_id, ...]`: The key ids of the recipients. The content is encrypted
+///   with a random data key, which is wrapped with the key of every recipient.
+/// - `$master_key_file`: The file which contains the hex encoded 32 byte master
+///   key. The content is encrypted with a random data key, which is wrapped by
+///   the master key.
+/// - `$variable` / `$password_file`: Only for `PASSWORD`. The environment
+///   variable or file which contains the password at compile time.
+/// - `$kdf`: Only for `PASSWORD`. The optional key derivation function. Either
+///   `"argon2"` (default) or `"pbkdf2"`.
+/// - `$public_key` / `$public_key_file`: Only for `X25519`. The hex encoded
+///   public key of the recipient or the file which contains it.
+/// - `$signing_key_file`: The file which contains the hex encoded ed25519
+///   secret key. If specified, the plaintext will be signed. It has to follow
+///   the file path directly.
+/// - `mac`: Embeds the HMAC-SHA256 of the encrypted buffer with its own random
+///   key. It has to follow the other arguments and can be used with every
+///   built-in encryption type, cascades, key ids, passwords and public keys.
+/// - `$algorithm($level)`: Compresses the file with `zstd`, `lz4`, `brotli`,
+///   `deflate` or `none` before it is encrypted. The level is optional and the
+///   cargo feature of the algorithm has to be enabled. It has to be the last
+///   argument or directly precede `mac`. Without it, the file is only
+///   compressed (with deflate) if the `compression` feature is set. The
+///   algorithm features on their own don't compress any file. If the compressed
+///   file isn't smaller, it's stored as it is.
 ///
 /// # Returns
 ///
-/// The macro expands to a `encrypt_xor` or `encrypt_aes` proc macro call. The
-/// return value will then be used to create a new `EncryptedFile` instance.
+/// The macro expands to a `encrypt_xor`, `encrypt_xor_stream`, `encrypt_aes`,
+/// `encrypt_aes_gcm`, `encrypt_chacha`, `encrypt_with`, `encrypt_external`,
+/// `encrypt_password`, `encrypt_x25519`, `encrypt_cascade`, `encrypt_mac`,
+/// `encrypt_envelope` or `encrypt_recipients` proc macro call.
+/// The return value will then be used to create a new `EncryptedFile` instance.
 ///
 /// # Examples
 ///
@@ -164,19 +1177,261 @@ impl EncryptedFile {
 /// // Encrypt using XOR with custom key
 /// let file: EncryptedFile = include_crypt!(XOR, "src/lib.rs", 0xdeadbeef);
 ///
+/// // Encrypt using a XOR keystream with random key
+/// let file: EncryptedFile = include_crypt!(XOR, "src/lib.rs", stream);
+///
+/// // Encrypt using a XOR keystream with custom key
+/// let file: EncryptedFile = include_crypt!(XOR, "src/lib.rs", 0xdeadbeef, stream);
+///
 /// // Encrypt using AES with random key
 /// let file: EncryptedFile = include_crypt!(AES, "src/lib.rs");
 ///
 /// // Encrypt using AES with custom key
 /// let file: EncryptedFile = include_crypt!(AES, "src/lib.rs", 0xdeadbeef);
+///
+/// // Encrypt using authenticated AES-GCM with random key
+/// let file: EncryptedFile = include_crypt!(AES_GCM, "src/lib.rs");
+///
+/// // Encrypt using authenticated AES-GCM with custom key
+/// let file: EncryptedFile = include_crypt!(AES_GCM, "src/lib.rs", 0xdeadbeef);
+///
+/// // Encrypt using authenticated XChaCha20-Poly1305 with random key
+/// let file: EncryptedFile = include_crypt!(CHACHA, "src/lib.rs");
+///
+/// // Encrypt using authenticated XChaCha20-Poly1305 with custom key
+/// let file: EncryptedFile = include_crypt!(CHACHA, "src/lib.rs", 0xdeadbeef);
+///
+/// // Encrypt using XOR and then AES, each with its own random key
+/// let file: EncryptedFile = include_crypt!(XOR + AES, "src/lib.rs");
 /// ```
 ///
 /// You can also use absolute paths:
 /// ```ignore
 /// let file: EncryptedFile = include_crypt!("D:/file.txt");
 /// ```
+///
+/// By default the keys, nonces and key shares are generated randomly, so every
+/// build produces a different binary. For reproducible builds, set the
+/// `INCLUDE_CRYPT_SEED` environment variable. All the key material is then
+/// derived from the seed, the macro and its arguments, the file path and the
+/// content of the file. The variable isn't tracked by cargo, so the crate has
+/// to be rebuilt after changing it.
+/// ```text
+/// INCLUDE_CRYPT_SEED=my-release-seed cargo build --release
+/// ```
+///
+/// The generated keys and nonces can be written to an escrow file, so that the
+/// embedded buffers can still be decrypted outside of the program, e.g. for
+/// incident analysis. Set the `INCLUDE_CRYPT_ESCROW` environment variable to
+/// the path of the escrow file, or to `1` to write it to
+/// `include-crypt-escrow.jsonl` in `OUT_DIR` or `CARGO_TARGET_DIR`. Every
+/// generated key is appended as JSON object on its own line together with the
+/// crate, the file path, the SHA-256 of the content and the id of the macro
+/// invocation. Specified keys, keyring keys and password keys are never
+/// written. The binary never references the
+/// escrow file, so it must be stored as securely as a signing key.
+///
+/// Custom ciphers can't be executed by the proc macro, so the file has to be
+/// sealed with `include_crypt::crypto::cipher::seal` ahead of time (e.g. in a
+/// build script). The custom cipher has to be a unit struct. Sealed files will
+/// never be compressed. The sealed file contains the key in plaintext, so it
+/// must not be stored anywhere the original file couldn't be stored.
+/// ```ignore
+/// let file: EncryptedFile = include_crypt!(my_crate::MyCipher, "assets/file.sealed");
+/// ```
+///
+/// The key can also be left out of the binary by specifying a key id. The key
+/// is then either specified explicitly or read from the environment variable
+/// of the key id at compile time (e.g. `INCLUDE_CRYPT_KEY_MY_KEY`). At runtime
+/// it has to be supplied by a [`KeyProvider`] with
+/// [`EncryptedFile::decrypt_with`].
+/// ```
+/// # use include_crypt::{EncryptedFile, EnvProvider, include_crypt};
+/// let file: EncryptedFile = include_crypt!(AES_GCM, "src/lib.rs", 0xdeadbeef, key_id = "my-key");
+///
+/// std::env::set_var("INCLUDE_CRYPT_KEY_MY_KEY", "deadbeef");
+/// let decrypted = file.decrypt_with(&EnvProvider).unwrap();
+/// ```
+///
+/// Keys can be rotated with a [`Keyring`], which holds the current and the
+/// previous keys by their id. The key of the key id is looked up in the keyring
+/// file at compile time. If no keyring file is specified, the one from the
+/// `INCLUDE_CRYPT_KEYRING` environment variable is used. At runtime the file is
+/// decrypted with [`EncryptedFile::decrypt_with_keyring`], which picks the key
+/// of its key id:
+/// ```ignore
+/// let file: EncryptedFile = include_crypt!(AES_GCM, "assets/file.txt", key_id = "2026-q3", keyring = "keys/keyring.toml");
+///
+/// let keyring = Keyring::from_file("/etc/my-app/keyring.toml")?;
+/// let decrypted = file.decrypt_with_keyring(&keyring)?;
+/// ```
+///
+/// With envelope encryption, the content is encrypted with a random data key
+/// and only the data key, wrapped by a master key, is embedded. The master key
+/// is either read from a file at compile time and embedded, or looked up by its
+/// id like a key id and supplied by a [`KeyProvider`] at runtime. Rotating the
+/// master key only rewraps the data key with
+/// `include_crypt::crypto::envelope::rewrap_key`.
+/// ```
+/// # use include_crypt::{EncryptedFile, include_crypt};
+/// let file: EncryptedFile =
+///     include_crypt!(AES_GCM, "src/lib.rs", master_key_file = "tests/master.key");
+/// ```
+/// ```ignore
+/// let file: EncryptedFile = include_crypt!(AES_GCM, "assets/file.txt", master_key_id = "master-2026");
+///
+/// let decrypted = file.decrypt_with(&EnvProvider)?;
+/// ```
+///
+/// The same file can be shipped to several recipients, e.g. customers with
+/// their own license key. The data key is wrapped once per recipient, and the
+/// keys of the recipients are looked up by their id at compile time like a key
+/// id. At runtime the file can be decrypted with
+/// [`EncryptedFile::decrypt_with`] or with the key of any recipient with
+/// [`EncryptedFile::decrypt_with_key`]:
+/// ```ignore
+/// let file: EncryptedFile = include_crypt!(
+///     AES_GCM,
+///     "assets/model.bin",
+///     recipients = ["customer-a", "customer-b"],
+///     keyring = "keys/licenses.toml"
+/// );
+///
+/// let decrypted = file.decrypt_with_key(&license_key)?;
+/// ```
+///
+/// Files can also be encrypted with a password. Only the salt and the
+/// parameters of the key derivation function are embedded, so the password
+/// has to be entered at runtime and passed to
+/// [`EncryptedFile::decrypt_with_password`].
+/// ```ignore
+/// let file: EncryptedFile = include_crypt!(PASSWORD, "assets/file.txt", env = "MY_PASSWORD");
+///
+/// let decrypted = file.decrypt_with_password(&password)?;
+/// ```
+///
+/// If only the public key should be present at compile time, files can be
+/// sealed to the x25519 public key of the recipient. The secret key has to be
+/// passed to [`EncryptedFile::decrypt_with_secret_key`] at runtime. Key pairs
+/// can be generated with `include_crypt::crypto::x25519::x25519_keypair`.
+/// ```ignore
+/// let file: EncryptedFile = include_crypt!(X25519, "assets/file.txt", public_key_file = "recipient.pub");
+///
+/// let decrypted = file.decrypt_with_secret_key(&secret_key)?;
+/// ```
+///
+/// The integrity of the encrypted buffer can be protected with a mac, so
+/// that a patched or corrupted buffer is refused with [`DecryptError::Mac`]
+/// before it is decrypted. This is mostly useful for `XOR`, `XOR` keystreams
+/// and `AES`, which aren't authenticated on their own.
+/// ```
+/// # use include_crypt::{EncryptedFile, include_crypt};
+/// let file: EncryptedFile = include_crypt!(XOR, "src/lib.rs", 0xdeadbeef, mac);
+/// let file: EncryptedFile = include_crypt!(XOR + AES, "src/lib.rs", mac);
+/// ```
+///
+/// The compression algorithm is selected per file and stored behind the
+/// compressed content, so a file is always decompressed with the algorithm it
+/// has been compressed with, no matter which features other crates enable:
+/// ```ignore
+/// let file: EncryptedFile = include_crypt!(AES_GCM, "assets/model.bin", compress = zstd(19));
+/// let file: EncryptedFile = include_crypt!(AES, "assets/index.html", compress = brotli, mac);
+/// let file: EncryptedFile = include_crypt!(AES, "assets/video.mp4", compress = none);
+/// ```
+///
+/// The plaintext can be signed with an ed25519 key, so that
+/// [`EncryptedFile::verify_and_decrypt`] refuses files that haven't been
+/// signed with the trusted key:
+/// ```ignore
+/// let file: EncryptedFile = include_crypt!(AES_GCM, "assets/update.json", sign = "keys/signing.key");
+///
+/// let decrypted = file.verify_and_decrypt(&PublicKey::from_hex(TRUSTED_KEY)?)?;
+/// ```
 #[macro_export]
 macro_rules! include_crypt {
+    ($enc_type:ident, $path:tt, sign = $key:literal $(, $($args:tt)*)?) => {{
+        let (signature, public_key) = $crate::codegen::sign_file!($path, $key);
+
+        $crate::include_crypt!($enc_type, $path $(, $($args)*)?).with_signature(signature, public_key)
+    }};
+    ($first:ident $(+ $layer:ident)+, $path:tt, sign = $key:literal $(, $($args:tt)*)?) => {{
+        let (signature, public_key) = $crate::codegen::sign_file!($path, $key);
+
+        $crate::include_crypt!($first $(+ $layer)+, $path $(, $($args)*)?).with_signature(signature, public_key)
+    }};
+    ($path:tt, sign = $key:literal $(, $($args:tt)*)?) => {{
+        let (signature, public_key) = $crate::codegen::sign_file!($path, $key);
+
+        $crate::include_crypt!($path $(, $($args)*)?).with_signature(signature, public_key)
+    }};
+
+    ($first:ident $(+ $layer:ident)+, $path:expr $(, $($args:tt)*)?) => {
+        $crate::codegen::encrypt_cascade!($first $(+ $layer)+, $path $(, $($args)*)?)
+    };
+
+    (X25519, $path:expr, $($args:tt)*) => {
+        $crate::codegen::encrypt_x25519!($path, $($args)*)
+    };
+
+    (PASSWORD, $path:expr, $($args:tt)*) => {
+        $crate::codegen::encrypt_password!($path, $($args)*)
+    };
+
+    ($enc_type:ident, $path:expr,recipients = $recipients:tt $(, $($args:tt)*)?) => {
+        $crate::codegen::encrypt_recipients!($enc_type, $path, recipients = $recipients $(, $($args)*)?)
+    };
+
+    ($enc_type:ident, $path:expr,master_key_file = $file:literal $(, $($args:tt)*)?) => {
+        $crate::codegen::encrypt_envelope!($enc_type, $path, master_key_file = $file $(, $($args)*)?)
+    };
+    ($enc_type:ident, $path:expr,master_key_id = $key_id:literal $(, $($args:tt)*)?) => {
+        $crate::codegen::encrypt_envelope!($enc_type, $path, master_key_id = $key_id $(, $($args)*)?)
+    };
+
+    ($enc_type:ident, $path:expr,key_id = $key_id:literal $(, $($args:tt)*)?) => {
+        $crate::codegen::encrypt_external!($enc_type, $path, key_id = $key_id $(, $($args)*)?)
+    };
+    ($enc_type:ident, $path:expr, $key:literal,key_id = $key_id:literal $(, $($args:tt)*)?) => {
+        $crate::codegen::encrypt_external!($enc_type, $path, $key, key_id = $key_id $(, $($args)*)?)
+    };
+
+    (XOR, $path:expr,stream,mac $(, $($args:tt)*)?) => {
+        $crate::codegen::encrypt_mac!(XOR_STREAM, $path, mac $(, $($args)*)?)
+    };
+    (XOR, $path:expr, $key:literal,stream,mac $(, $($args:tt)*)?) => {
+        $crate::codegen::encrypt_mac!(XOR_STREAM, $path, $key, mac $(, $($args)*)?)
+    };
+    ($enc_type:ident, $path:expr,mac $(, $($args:tt)*)?) => {
+        $crate::codegen::encrypt_mac!($enc_type, $path, mac $(, $($args)*)?)
+    };
+    ($enc_type:ident, $path:expr, $key:literal,mac $(, $($args:tt)*)?) => {
+        $crate::codegen::encrypt_mac!($enc_type, $path, $key, mac $(, $($args)*)?)
+    };
+
+    (XOR, $path:expr,stream,compress = $($args:tt)*) => {
+        $crate::codegen::encrypt_mac!(XOR_STREAM, $path, compress = $($args)*)
+    };
+    (XOR, $path:expr, $key:literal,stream,compress = $($args:tt)*) => {
+        $crate::codegen::encrypt_mac!(XOR_STREAM, $path, $key, compress = $($args)*)
+    };
+    ($enc_type:ident, $path:expr,compress = $($args:tt)*) => {
+        $crate::codegen::encrypt_mac!($enc_type, $path, compress = $($args)*)
+    };
+    ($enc_type:ident, $path:expr, $key:literal,compress = $($args:tt)*) => {
+        $crate::codegen::encrypt_mac!($enc_type, $path, $key, compress = $($args)*)
+    };
+
+    (XOR, $path:expr,stream) => {{
+        let (key, nonce, data) = $crate::codegen::encrypt_xor_stream!($path);
+
+        $crate::EncryptedFile::new(data, $crate::EncryptionType::XorStream(key, nonce))
+    }};
+    (XOR, $path:expr, $key:expr,stream) => {{
+        let (key, nonce, data) = $crate::codegen::encrypt_xor_stream!($path, $key);
+
+        $crate::EncryptedFile::new(data, $crate::EncryptionType::XorStream(key, nonce))
+    }};
+
     (XOR, $path:expr) => {{
         let (key, data) = $crate::codegen::encrypt_xor!($path);
 
@@ -199,9 +1454,43 @@ macro_rules! include_crypt {
         $crate::EncryptedFile::new(data, $crate::EncryptionType::Aes(key, nonce))
     }};
 
+    (AES_GCM, $path:expr) => {{
+        let (key, nonce, tag, data) = $crate::codegen::encrypt_aes_gcm!($path);
+
+        $crate::EncryptedFile::new(data, $crate::EncryptionType::AesGcm(key, nonce, tag))
+    }};
+    (AES_GCM, $path:expr, $key:expr) => {{
+        let (key, nonce, tag, data) = $crate::codegen::encrypt_aes_gcm!($path, $key);
+
+        $crate::EncryptedFile::new(data, $crate::EncryptionType::AesGcm(key, nonce, tag))
+    }};
+
+    (CHACHA, $path:expr) => {{
+        let (key, nonce, tag, data) = $crate::codegen::encrypt_chacha!($path);
+
+        $crate::EncryptedFile::new(data, $crate::EncryptionType::ChaCha(key, nonce, tag))
+    }};
+    (CHACHA, $path:expr, $key:expr) => {{
+        let (key, nonce, tag, data) = $crate::codegen::encrypt_chacha!($path, $key);
+
+        $crate::EncryptedFile::new(data, $crate::EncryptionType::ChaCha(key, nonce, tag))
+    }};
+
+    ($cipher:path, $path:expr) => {{
+        let (key, nonce, tag, data) = $crate::codegen::encrypt_with!($cipher, $path);
+
+        $crate::EncryptedFile::new(data, $crate::EncryptionType::Custom(&$cipher, key, nonce, tag))
+    }};
+
     ($path:expr) => {
         $crate::include_crypt!(XOR, $path)
     };
+    ($path:expr,mac $(, $($args:tt)*)?) => {
+        $crate::include_crypt!(XOR, $path, mac $(, $($args)*)?)
+    };
+    ($path:expr,compress = $($args:tt)*) => {
+        $crate::include_crypt!(XOR, $path, compress = $($args)*)
+    };
     ($path:expr, $key:expr) => {
         $crate::include_crypt!(XOR, $path, $key)
     };
@@ -212,9 +1501,36 @@ macro_rules! include_crypt {
 pub struct EncryptedFolder<'a> {
     #[doc(hidden)]
     pub files: &'a [(&'static str, EncryptedFile)],
+
+    #[doc(hidden)]
+    pub signature: Option<(&'static [u8; ED25519_SIGNATURE_LEN], PublicKey)>,
 }
 
 impl<'a> EncryptedFolder<'a> {
+    /// Creates a new folder with the encrypted files.
+    ///
+    /// # Parameters
+    ///
+    /// - `files`: The hashed relative paths and the encrypted files. This will
+    ///   be the output of the `include_files` proc macro.
+    pub const fn new(files: &'a [(&'static str, EncryptedFile)]) -> Self { Self { files, signature: None } }
+
+    /// Adds the signature of the manifest of all the files.
+    ///
+    /// # Parameters
+    ///
+    /// - `signature`: The ed25519 signature of the manifest. This will be the
+    ///   output of the `sign_files` proc macro.
+    /// - `public_key`: The public key of the signer. It is only embedded for
+    ///   informational purposes, the signature has to be verified with a
+    ///   trusted public key.
+    pub const fn with_signature(self, signature: &'static [u8; ED25519_SIGNATURE_LEN], public_key: PublicKey) -> Self {
+        Self {
+            signature: Some((signature, public_key)),
+            ..self
+        }
+    }
+
     /// Tries to find the file in the folder.
     ///
     /// # Parameters
@@ -258,6 +1574,197 @@ impl<'a> EncryptedFolder<'a> {
 
         None
     }
+
+    /// Decrypts all the files in the folder and verifies the signature of the
+    /// manifest. The data key of the folder is unwrapped with the embedded
+    /// master key (see [`EncryptedFolder::unwrap_key`]).
+    ///
+    /// # Parameters
+    ///
+    /// - `public_key`: The trusted public key of the signer.
+    ///
+    /// # Returns
+    ///
+    /// If the folder hasn't been signed or the signature of the manifest
+    /// doesn't match, `Err(DecryptError::Signature)` will be returned. If the
+    /// master key has to be supplied at runtime,
+    /// `Err(DecryptError::MissingKey)` will be returned (see
+    /// [`EncryptedFolder::verify_with`]). If one of the files couldn't be
+    /// decrypted, the error will be returned.
+    pub fn verify(&self, public_key: &PublicKey) -> Result<(), DecryptError> { self.unwrap_key()?.verify(public_key) }
+
+    /// Decrypts all the files in the folder and verifies the signature of the
+    /// manifest. The data key of the folder is unwrapped with the master key
+    /// from the key provider (see [`EncryptedFolder::unwrap_key_with`]).
+    ///
+    /// # Parameters
+    ///
+    /// - `public_key`: The trusted public key of the signer.
+    /// - `provider`: The provider that supplies the master key of the folder.
+    ///
+    /// # Returns
+    ///
+    /// See [`EncryptedFolder::verify`] and
+    /// [`EncryptedFolder::unwrap_key_with`].
+    pub fn verify_with(&self, public_key: &PublicKey, provider: &dyn KeyProvider) -> Result<(), DecryptError> {
+        self.unwrap_key_with(provider)?.verify(public_key)
+    }
+
+    /// Returns the embedded public key of the signer if the folder has been
+    /// signed.
+    pub fn public_key(&self) -> Option<&PublicKey> { self.signature.as_ref().map(|(_, public_key)| public_key) }
+
+    /// Returns the wrapped data key if the files of the folder have been
+    /// encrypted with a data key.
+    pub fn wrapped_key(&self) -> Option<&'static WrappedKey> {
+        self.files.iter().find_map(|(_, file)| file.enc_type.wrapped_key())
+    }
+
+    /// Unwraps the data key of the folder with the embedded master key, so that
+    /// the files can be decrypted without unwrapping it again. Folders without
+    /// a data key use the embedded keys of the files.
+    ///
+    /// # Returns
+    ///
+    /// If the master key is not embedded, `Err(DecryptError::MissingKey)`
+    /// will be returned. If the wrapped key is invalid,
+    /// `Err(DecryptError::Integrity)` will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use include_crypt::{include_dir, EncryptedFolder};
+    /// let folder: EncryptedFolder =
+    ///     include_dir!(AES_GCM, "./src", master_key_file = "tests/master.key");
+    ///
+    /// let folder = folder.unwrap_key().unwrap();
+    /// assert!(folder.decrypt("lib.rs").unwrap().is_ok());
+    /// ```
+    pub fn unwrap_key(&self) -> Result<UnwrappedFolder<'_>, DecryptError> {
+        let data_key = self
+            .wrapped_key()
+            .map(|wrapped| wrapped.unwrap_data_key(None))
+            .transpose()?;
+
+        Ok(UnwrappedFolder { folder: self, data_key })
+    }
+
+    /// Unwraps the data key of the folder with the master key from the key
+    /// provider (see [`EncryptedFolder::unwrap_key`]).
+    ///
+    /// # Returns
+    ///
+    /// If the provider has no key for the id of the master key,
+    /// `Err(DecryptError::MissingKey)` will be returned. If the master key is
+    /// wrong, `Err(DecryptError::Integrity)` will be returned.
+    pub fn unwrap_key_with(&self, provider: &dyn KeyProvider) -> Result<UnwrappedFolder<'_>, DecryptError> {
+        let data_key = self
+            .wrapped_key()
+            .map(|wrapped| wrapped.unwrap_data_key(Some(provider)))
+            .transpose()?;
+
+        Ok(UnwrappedFolder { folder: self, data_key })
+    }
+}
+
+/// A folder whose data key has been unwrapped. The data key is wiped when the
+/// folder is dropped.
+///
+/// The folder is returned by [`EncryptedFolder::unwrap_key`] and
+/// [`EncryptedFolder::unwrap_key_with`].
+pub struct UnwrappedFolder<'a> {
+    folder: &'a EncryptedFolder<'a>,
+    data_key: Option<EncryptionKey>,
+}
+
+impl<'a> UnwrappedFolder<'a> {
+    /// Decrypts the file in the folder with the unwrapped data key.
+    ///
+    /// # Parameters
+    ///
+    /// - `file_path`: The relative path to the file in the folder.
+    ///
+    /// # Returns
+    ///
+    /// If the file couldn't be found, `None` will be returned. Otherwise see
+    /// [`EncryptedFile::try_decrypt`].
+    pub fn decrypt(&self, file_path: &str) -> Option<Result<Vec<u8>, DecryptError>> {
+        let file = self.folder.get(file_path)?;
+        let key = self.data_key.as_ref().map(|key| EncryptionKey::from(key.to_vec()));
+
+        Some(file.decrypt_key(key).map(|mut data| take(&mut data)))
+    }
+
+    /// Returns the content of a file of a solid archive without copying it
+    /// (see [`EncryptedFile::decrypt_slice`]). The archive is decrypted with
+    /// the unwrapped data key.
+    ///
+    /// # Parameters
+    ///
+    /// - `file_path`: The relative path to the file in the folder.
+    ///
+    /// # Returns
+    ///
+    /// If the file couldn't be found or isn't part of a solid archive, `None`
+    /// will be returned. Otherwise see [`EncryptedFile::try_decrypt`].
+    pub fn decrypt_slice(&self, file_path: &str) -> Option<Result<&'static [u8], DecryptError>> {
+        let file = self.folder.get(file_path)?;
+        let key = self.data_key.as_ref().map(|key| EncryptionKey::from(key.to_vec()));
+
+        match file.enc_type {
+            EncryptionType::Solid(archive, file_id) => Some(archive.get(file_id, key)),
+            _ => None,
+        }
+    }
+
+    /// Decrypts all the files in the folder with the unwrapped data key and
+    /// verifies the signature of the manifest.
+    ///
+    /// # Parameters
+    ///
+    /// - `public_key`: The trusted public key of the signer.
+    ///
+    /// # Returns
+    ///
+    /// If the folder hasn't been signed or the signature of the manifest
+    /// doesn't match, `Err(DecryptError::Signature)` will be returned. If one
+    /// of the files couldn't be decrypted, the error will be returned.
+    pub fn verify(&self, public_key: &PublicKey) -> Result<(), DecryptError> {
+        let signature = match self.folder.signature {
+            Some((signature, _)) => signature,
+            None => return Err(DecryptError::Signature),
+        };
+
+        let files = self
+            .folder
+            .files
+            .iter()
+            .map(|(path, file)| {
+                let key = self.data_key.as_ref().map(|key| EncryptionKey::from(key.to_vec()));
+                file.decrypt_key(key).map(|data| (*path, data))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let manifest = ed25519_manifest(files.iter().map(|(path, data)| (*path, data.as_slice())));
+
+        if ed25519_verify(&manifest, public_key, &signature[..]) {
+            Ok(())
+        } else {
+            Err(DecryptError::Signature)
+        }
+    }
+
+    /// Returns the folder.
+    pub fn folder(&self) -> &EncryptedFolder<'a> { self.folder }
+}
+
+/// The data key is never printed.
+impl fmt::Debug for UnwrappedFolder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnwrappedFolder")
+            .field("files", &self.folder.files.len())
+            .field("unwrapped", &self.data_key.is_some())
+            .finish()
+    }
 }
 
 /// Macro that can be used to safely embed a folder into the binary.
@@ -268,13 +1775,38 @@ impl<'a> EncryptedFolder<'a> {
 ///
 /// ```ignore
 /// include_dir!($encryption_type, $folder_path)
+/// include_dir!(XOR, $folder_path, stream)
+/// include_dir!($encryption_type, $folder_path, sign = $signing_key_file, ...)
+/// include_dir!($encryption_type, $folder_path, mac)
+/// include_dir!($encryption_type, $folder_path, master_key_file = $master_key_file)
+/// include_dir!($encryption_type, $folder_path, master_key_id = $key_id, keyring = $keyring_file)
+/// include_dir!($encryption_type, $folder_path, ..., compress = $algorithm($level))
+/// include_dir!($encryption_type, $folder_path, ..., solid)
 /// ```
 ///
-/// - `$encryption_type`: The type of the encryption. Either `XOR` or `AES`. If
-///   you don't specify an encryption type, `XOR` will be used.
+/// - `$encryption_type`: The type of the encryption. Either `XOR`, `AES`,
+///   `AES_GCM`, `CHACHA` or the path to a custom type that implements the
+///   `Cipher` trait. If you don't specify an encryption type, `XOR` will be
+///   used.
 /// - `$folder_path`: The path to the folder that should be embedded. If the
 ///   path is relative, the `CARGO_MANIFEST_DIR` will be used as a starting
 ///   point.
+/// - `stream`: Only for `XOR`. Instead of repeating the key over the data, a
+///   keystream will be generated from the key and a random nonce.
+/// - `$signing_key_file`: The file which contains the hex encoded ed25519
+///   secret key. If specified, a manifest of all the files will be signed.
+/// - `mac`: Embeds the HMAC-SHA256 of every encrypted file (see
+///   [`include_crypt`]).
+/// - `$master_key_file` / `$key_id`: Encrypts all the files with one random
+///   data key, which is wrapped by the master key (see [`include_crypt`]).
+/// - `$algorithm($level)`: Compresses every file with the algorithm (see
+///   [`include_crypt`]). Files of custom ciphers can't be compressed. Images,
+///   archives, fonts and media files are already compressed and always stored
+///   as they are. With `zstd_dict`, a zstd dictionary is trained on all the
+///   files of the folder and embedded encrypted next to them, which shrinks
+///   folders of many small, similar files.
+/// - `solid`: Combines all the files into one archive, which is compressed and
+///   encrypted at once. Files of custom ciphers can't be combined.
 ///
 /// # Returns
 ///
@@ -292,29 +1824,132 @@ impl<'a> EncryptedFolder<'a> {
 /// // Encrypt using XOR with random key
 /// let folder: EncryptedFolder = include_dir!(XOR, "./src");
 ///
+/// // Encrypt using a XOR keystream with random key
+/// let folder: EncryptedFolder = include_dir!(XOR, "./src", stream);
+///
 /// // Encrypt using AES with random key
 /// let folder: EncryptedFolder = include_dir!(AES, "./src");
+///
+/// // Encrypt using authenticated AES-GCM with random key
+/// let folder: EncryptedFolder = include_dir!(AES_GCM, "./src");
+///
+/// // Encrypt using authenticated XChaCha20-Poly1305 with random key
+/// let folder: EncryptedFolder = include_dir!(CHACHA, "./src");
+///
+/// // Encrypt using AES with random key and embed the mac of every file
+/// let folder: EncryptedFolder = include_dir!(AES, "./src", mac);
 /// ```
 ///
 /// You can also use absolute paths:
 /// ```ignore
 /// let folder: EncryptedFolder = include_dir!("D:/assets");
 /// ```
+///
+/// Only one random folder key is embedded for the built-in encryption types.
+/// The key and nonce of every file are derived from the folder key and the
+/// hashed relative path of the file with HKDF-SHA256 when the file is
+/// decrypted, so large folders don't embed a key for every file.
+///
+/// All the files in the folder can also be sealed with a custom cipher (see
+/// [`include_crypt`]):
+/// ```ignore
+/// let folder: EncryptedFolder = include_dir!(my_crate::MyCipher, "sealed");
+/// ```
+///
+/// The data key of a folder with a master key only has to be unwrapped once
+/// with [`EncryptedFolder::unwrap_key`] or
+/// [`EncryptedFolder::unwrap_key_with`]:
+/// ```ignore
+/// let folder: EncryptedFolder = include_dir!(AES_GCM, "assets", master_key_id = "master-2026");
+///
+/// let folder = folder.unwrap_key_with(&EnvProvider)?;
+/// let decrypted = folder.decrypt("file.txt").unwrap()?;
+/// ```
+///
+/// The manifest of a signed folder can be verified with
+/// [`EncryptedFolder::verify`]:
+/// ```ignore
+/// let folder: EncryptedFolder = include_dir!(AES_GCM, "scripts", sign = "keys/signing.key");
+///
+/// folder.verify(&PublicKey::from_hex(TRUSTED_KEY)?)?;
+/// ```
+///
+/// Folders of many tiny files compress much better as one solid archive. The
+/// index of the files is encrypted with the archive and only the key of the
+/// archive is embedded. The archive is decrypted and decompressed once, when
+/// the first file is decrypted, and stays in memory (see [`SolidArchive`]).
+/// `decrypt` copies the file out of the archive, `decrypt_slice` borrows it:
+/// ```
+/// # use include_crypt::{include_dir, EncryptedFolder};
+/// static FOLDER: EncryptedFolder = include_dir!(AES, "./src", solid);
+///
+/// let decrypted = FOLDER.get("lib.rs").unwrap().decrypt();
+/// let borrowed: &'static [u8] = FOLDER.get("lib.rs").unwrap().decrypt_slice().unwrap()?;
+/// # Ok::<(), include_crypt::DecryptError>(())
+/// ```
 #[macro_export]
 macro_rules! include_dir {
-    (XOR, $path:expr) => {
-        $crate::EncryptedFolder {
-            files: &$crate::codegen::include_files!("XOR", $path),
-        }
+    (@files XOR, $path:expr,stream $(, $($args:tt)*)?) => {{
+        static FILES: &[(&str, $crate::EncryptedFile)] =
+            &$crate::codegen::include_files!("XOR_STREAM", $path $(, $($args)*)?);
+        FILES
+    }};
+    (@files XOR, $path:expr $(, $($args:tt)*)?) => {{
+        static FILES: &[(&str, $crate::EncryptedFile)] =
+            &$crate::codegen::include_files!("XOR", $path $(, $($args)*)?);
+        FILES
+    }};
+    (@files AES, $path:expr $(, $($args:tt)*)?) => {{
+        static FILES: &[(&str, $crate::EncryptedFile)] =
+            &$crate::codegen::include_files!("AES", $path $(, $($args)*)?);
+        FILES
+    }};
+    (@files AES_GCM, $path:expr $(, $($args:tt)*)?) => {{
+        static FILES: &[(&str, $crate::EncryptedFile)] =
+            &$crate::codegen::include_files!("AES_GCM", $path $(, $($args)*)?);
+        FILES
+    }};
+    (@files CHACHA, $path:expr $(, $($args:tt)*)?) => {{
+        static FILES: &[(&str, $crate::EncryptedFile)] =
+            &$crate::codegen::include_files!("CHACHA", $path $(, $($args)*)?);
+        FILES
+    }};
+    (@files $cipher:path, $path:expr $(, $($args:tt)*)?) => {{
+        static FILES: &[(&str, $crate::EncryptedFile)] =
+            &$crate::codegen::include_files!($cipher, $path $(, $($args)*)?);
+        FILES
+    }};
+
+    ($enc_type:ident, $path:tt, sign = $key:literal $(, $($args:tt)*)?) => {{
+        let (signature, public_key) = $crate::codegen::sign_files!($path, $key);
+
+        $crate::EncryptedFolder::new($crate::include_dir!(@files $enc_type, $path $(, $($args)*)?))
+            .with_signature(signature, public_key)
+    }};
+    ($path:tt, sign = $key:literal) => {
+        $crate::include_dir!(XOR, $path, sign = $key)
     };
 
-    (AES, $path:expr) => {
-        $crate::EncryptedFolder {
-            files: &$crate::codegen::include_files!("AES", $path),
-        }
+    (XOR, $path:expr,stream $(, $($args:tt)*)?) => {
+        $crate::EncryptedFolder::new($crate::include_dir!(@files XOR, $path, stream $(, $($args)*)?))
+    };
+    (XOR, $path:expr $(, $($args:tt)*)?) => {
+        $crate::EncryptedFolder::new($crate::include_dir!(@files XOR, $path $(, $($args)*)?))
+    };
+    (AES, $path:expr $(, $($args:tt)*)?) => {
+        $crate::EncryptedFolder::new($crate::include_dir!(@files AES, $path $(, $($args)*)?))
+    };
+    (AES_GCM, $path:expr $(, $($args:tt)*)?) => {
+        $crate::EncryptedFolder::new($crate::include_dir!(@files AES_GCM, $path $(, $($args)*)?))
+    };
+    (CHACHA, $path:expr $(, $($args:tt)*)?) => {
+        $crate::EncryptedFolder::new($crate::include_dir!(@files CHACHA, $path $(, $($args)*)?))
+    };
+    ($cipher:path, $path:expr $(, $($args:tt)*)?) => {
+        $crate::EncryptedFolder::new($crate::include_dir!(@files $cipher, $path $(, $($args)*)?))
     };
 
-    ($path:expr) => {
-        $crate::include_dir!(XOR, $path)
+    ($path:expr $(, $($args:tt)*)?) => {
+        $crate::include_dir!(XOR, $path $(, $($args)*)?)
     };
 }
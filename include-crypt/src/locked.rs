@@ -0,0 +1,163 @@
+use crate::{DecryptError, Plaintext};
+use std::{fmt, ops::Deref, ptr, slice};
+use zeroize::Zeroize;
+
+/// The decrypted buffer of an embedded file in memory that has been mapped
+/// separately from the heap. The pages are excluded from core dumps, locked
+/// into memory and read-only. When the buffer is dropped, the pages will be
+/// overwritten with zeros and unmapped.
+///
+/// The buffer is returned by [`EncryptedFile::decrypt_locked`].
+///
+/// [`EncryptedFile::decrypt_locked`]: crate::EncryptedFile::decrypt_locked
+pub struct LockedBuffer {
+    /// The start of the mapped pages.
+    ptr: *mut u8,
+
+    /// The length of the decrypted buffer.
+    len: usize,
+
+    /// The size of the mapped pages.
+    size: usize,
+
+    /// Whether the pages could be locked into memory.
+    locked: bool,
+}
+
+// The pages are read-only as long as the buffer is shared.
+//
+unsafe impl Send for LockedBuffer {}
+unsafe impl Sync for LockedBuffer {}
+
+impl LockedBuffer {
+    /// Maps new writable pages for at least `capacity` bytes. The pages are
+    /// excluded from core dumps and locked before anything is written into
+    /// them.
+    ///
+    /// # Returns
+    ///
+    /// If the pages couldn't be mapped, `None` will be returned. If the pages
+    /// can't be locked, because `RLIMIT_MEMLOCK` is too small, the buffer will
+    /// still be returned (see [`LockedBuffer::is_locked`]).
+    fn map(capacity: usize) -> Option<Self> {
+        // An empty mapping is not allowed, so at least one page is mapped.
+        //
+        let page_size = match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+            size if size > 0 => size as usize,
+            _ => return None,
+        };
+        let pages = capacity.max(1).checked_add(page_size - 1)? / page_size;
+        let size = pages.checked_mul(page_size)?;
+
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return None;
+        }
+
+        // Locking fails if it exceeds `RLIMIT_MEMLOCK`, in which case the pages
+        // can still be swapped out.
+        //
+        let locked = unsafe {
+            libc::madvise(ptr, size, libc::MADV_DONTDUMP);
+            libc::mlock(ptr, size) == 0
+        };
+
+        Some(Self {
+            ptr: ptr as *mut u8,
+            len: 0,
+            size,
+            locked,
+        })
+    }
+
+    /// Makes the pages read-only once the file has been decrypted into them.
+    ///
+    /// # Returns
+    ///
+    /// If the pages couldn't be protected, they will be wiped and `None` will
+    /// be returned.
+    pub(crate) fn protect(self) -> Option<Self> {
+        match unsafe { libc::mprotect(self.ptr as *mut libc::c_void, self.size, libc::PROT_READ) } {
+            0 => Some(self),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the pages are locked into memory. This is `false` if
+    /// `RLIMIT_MEMLOCK` is too small.
+    pub fn is_locked(&self) -> bool { self.locked }
+}
+
+/// The file is decrypted and decompressed in the locked pages, before they are
+/// made read-only.
+impl Plaintext for LockedBuffer {
+    fn with_capacity(capacity: usize) -> Result<Self, DecryptError> { Self::map(capacity).ok_or(DecryptError::Memory) }
+
+    fn spare_capacity(&self) -> usize { self.size - self.len }
+
+    fn append(&mut self, data: &[u8]) {
+        assert!(data.len() <= self.spare_capacity());
+
+        unsafe { ptr::copy_nonoverlapping(data.as_ptr(), self.ptr.add(self.len), data.len()) };
+        self.len += data.len();
+    }
+
+    fn as_slice(&self) -> &[u8] { self }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] { unsafe { slice::from_raw_parts_mut(self.ptr, self.len) } }
+
+    fn without_id(mut self) -> Self {
+        self.len = self.len.saturating_sub(1);
+        self
+    }
+}
+
+impl Drop for LockedBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            let ptr = self.ptr as *mut libc::c_void;
+
+            // The pages are leaked if they can't be made writable again, because the
+            // plaintext couldn't be wiped otherwise.
+            //
+            if libc::mprotect(ptr, self.size, libc::PROT_READ | libc::PROT_WRITE) != 0 {
+                return;
+            }
+            slice::from_raw_parts_mut(self.ptr, self.size).zeroize();
+
+            if self.locked {
+                libc::munlock(ptr, self.size);
+            }
+            libc::munmap(ptr, self.size);
+        }
+    }
+}
+
+impl Deref for LockedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target { unsafe { slice::from_raw_parts(self.ptr, self.len) } }
+}
+
+impl AsRef<[u8]> for LockedBuffer {
+    fn as_ref(&self) -> &[u8] { self }
+}
+
+/// The plaintext is never printed.
+impl fmt::Debug for LockedBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LockedBuffer")
+            .field("len", &self.len)
+            .field("locked", &self.locked)
+            .finish()
+    }
+}
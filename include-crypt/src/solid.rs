@@ -0,0 +1,146 @@
+use crate::{
+    crypto::{
+        archive::solid_index,
+        key::EncryptionKey,
+        mac::{hmac_sha256, hmac_verify, MAC_TAG_LEN},
+    },
+    DecryptError, EncryptedFile,
+};
+use std::{
+    fmt,
+    ops::Range,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+use zeroize::Zeroizing;
+
+/// The compressed and encrypted archive of all the files of a solid folder.
+/// The archive is decrypted and decompressed once, when the first file is
+/// accessed, and kept in memory for the rest of the program. The files are
+/// copied out of it by the `decrypt` functions, or borrowed from it by
+/// [`EncryptedFile::decrypt_slice`] and [`UnwrappedFolder::decrypt_slice`].
+///
+/// If the archive is encrypted with a master key that has to be supplied at
+/// runtime, the decrypted archive is only returned to calls that supply the
+/// same key as the call that decrypted it. The other calls fail with
+/// `DecryptError::MissingKey` or `DecryptError::Integrity`, even if the
+/// archive has already been decrypted. Errors aren't cached, so a failed call
+/// can be retried, e.g. with the right key.
+///
+/// The archive is generated by `include_dir!` if `solid` is specified (see
+/// [`include_dir`]).
+///
+/// [`include_dir`]: crate::include_dir
+/// [`UnwrappedFolder::decrypt_slice`]: crate::UnwrappedFolder::decrypt_slice
+pub struct SolidArchive {
+    /// The encrypted archive with the index.
+    file: EncryptedFile,
+
+    /// The decrypted archive and the index of its files.
+    entries: OnceLock<Entries>,
+
+    /// Makes sure that the archive is only decrypted by one thread at once.
+    lock: Mutex<()>,
+}
+
+/// The decrypted archive with the id and the range of every file.
+struct Entries {
+    data: Zeroizing<Vec<u8>>,
+    index: Vec<(String, Range<usize>)>,
+
+    /// The mac of the key that has been supplied to decrypt the archive, if
+    /// any. Only the key itself can reproduce it.
+    key_mac: Option<[u8; MAC_TAG_LEN]>,
+}
+
+/// The data whose mac identifies the key of the archive.
+const KEY_MAC_DATA: &[u8] = b"include-crypt solid archive key";
+
+impl SolidArchive {
+    /// Creates a new archive which hasn't been decrypted yet.
+    ///
+    /// # Parameters
+    ///
+    /// - `file`: The encrypted archive. This will be generated by the
+    ///   `include_files` proc macro.
+    pub const fn new(file: EncryptedFile) -> Self {
+        Self {
+            file,
+            entries: OnceLock::new(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Returns the encrypted archive.
+    pub(crate) fn file(&self) -> &EncryptedFile { &self.file }
+
+    /// Returns the decrypted content of the file. The archive is decrypted with
+    /// the key if it hasn't been decrypted yet.
+    ///
+    /// # Returns
+    ///
+    /// If the key has to be supplied at runtime and hasn't been,
+    /// `Err(DecryptError::MissingKey)` will be returned. If the archive has
+    /// been decrypted with another key, `Err(DecryptError::Integrity)` will be
+    /// returned. If the index is invalid or doesn't contain the file,
+    /// `Err(DecryptError::Archive)` will be returned. Otherwise see
+    /// [`EncryptedFile::try_decrypt`].
+    pub(crate) fn get(&self, file_id: &str, key: Option<EncryptionKey>) -> Result<&[u8], DecryptError> {
+        if key.is_none() && self.file.key_id().is_some() {
+            return Err(DecryptError::MissingKey);
+        }
+
+        let entries = self.entries(key)?;
+        entries
+            .index
+            .iter()
+            .find(|(id, _)| id == file_id)
+            .map(|(_, range)| &entries.data[range.clone()])
+            .ok_or(DecryptError::Archive)
+    }
+
+    /// Returns the decrypted archive. Other threads wait until it has been
+    /// decrypted, so that it's only decrypted once. Archives whose key has to
+    /// be supplied at runtime are only returned if the key matches the key
+    /// that has decrypted them.
+    fn entries(&self, key: Option<EncryptionKey>) -> Result<&Entries, DecryptError> {
+        if let Some(entries) = self.entries.get() {
+            return self.check_key(entries, key.as_ref());
+        }
+
+        let _lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(entries) = self.entries.get() {
+            return self.check_key(entries, key.as_ref());
+        }
+
+        let key_mac = key.as_ref().map(|key| hmac_sha256(KEY_MAC_DATA, key));
+        let data = self.file.decrypt_key(key)?;
+        let index = solid_index(&data).ok_or(DecryptError::Archive)?;
+
+        Ok(self.entries.get_or_init(|| Entries { data, index, key_mac }))
+    }
+
+    /// Makes sure that the key of an archive whose key has to be supplied at
+    /// runtime is the key that has decrypted it. The key is supplied by every
+    /// call of these archives (see [`SolidArchive::get`]).
+    fn check_key<'a>(&self, entries: &'a Entries, key: Option<&EncryptionKey>) -> Result<&'a Entries, DecryptError> {
+        if self.file.key_id().is_none() {
+            return Ok(entries);
+        }
+
+        match (key, &entries.key_mac) {
+            (Some(key), Some(key_mac)) if hmac_verify(KEY_MAC_DATA, key, key_mac) => Ok(entries),
+            (None, _) => Err(DecryptError::MissingKey),
+            _ => Err(DecryptError::Integrity),
+        }
+    }
+}
+
+/// The decrypted archive is never printed.
+impl fmt::Debug for SolidArchive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SolidArchive")
+            .field("len", &self.file.buffer.len())
+            .field("decrypted", &self.entries.get().is_some())
+            .finish()
+    }
+}
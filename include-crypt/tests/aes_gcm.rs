@@ -0,0 +1,31 @@
+use include_crypt::{codegen::encrypt_aes_gcm, include_crypt, DecryptError, EncryptedFile, EncryptionType};
+
+#[test]
+fn test_aes_gcm_no_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(AES_GCM, "tests/test.data");
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_aes_gcm_custom_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(AES_GCM, "tests/test.data", 0xABCDEF0123456789);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_aes_gcm_tampered() {
+    let (key, nonce, tag, data) = encrypt_aes_gcm!("tests/test.data");
+
+    let mut tampered = data.to_vec();
+    tampered[0] ^= 1;
+
+    let file = EncryptedFile::new(
+        Box::leak(tampered.into_boxed_slice()),
+        EncryptionType::AesGcm(key, nonce, tag),
+    );
+    assert_eq!(file.try_decrypt(), Err(DecryptError::Integrity));
+}
@@ -0,0 +1,41 @@
+use include_crypt::{codegen::encrypt_aes_gcm, include_crypt, DecryptError, EncryptedFile, EncryptionType, PublicKey};
+
+#[test]
+fn test_cascade_xor_aes() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(XOR + AES, "tests/test.data");
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_cascade_all() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(XOR_STREAM + AES + AES_GCM + CHACHA, "tests/test.data");
+    assert_eq!(file.try_decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_cascade_signed() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+    let public_key = PublicKey::from_hex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+
+    let file = include_crypt!(AES + CHACHA, "tests/test.data", sign = "tests/signing.key");
+    assert_eq!(file.verify_and_decrypt(&public_key).unwrap(), content.as_bytes());
+}
+
+#[test]
+fn test_cascade_tampered() {
+    let (key, nonce, tag, data) = encrypt_aes_gcm!("tests/test.data");
+
+    let mut tampered = data.to_vec();
+    tampered[0] ^= 1;
+
+    let layers = vec![EncryptionType::AesGcm(key, nonce, tag)];
+    let file = EncryptedFile::new(
+        Box::leak(tampered.into_boxed_slice()),
+        EncryptionType::Cascade(Box::leak(layers.into_boxed_slice())),
+    );
+    assert_eq!(file.try_decrypt(), Err(DecryptError::Integrity));
+}
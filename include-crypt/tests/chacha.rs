@@ -0,0 +1,31 @@
+use include_crypt::{codegen::encrypt_chacha, include_crypt, DecryptError, EncryptedFile, EncryptionType};
+
+#[test]
+fn test_chacha_no_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(CHACHA, "tests/test.data");
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_chacha_custom_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(CHACHA, "tests/test.data", 0xABCDEF0123456789);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_chacha_tampered() {
+    let (key, nonce, tag, data) = encrypt_chacha!("tests/test.data");
+
+    let mut tampered = data.to_vec();
+    tampered[0] ^= 1;
+
+    let file = EncryptedFile::new(
+        Box::leak(tampered.into_boxed_slice()),
+        EncryptionType::ChaCha(key, nonce, tag),
+    );
+    assert_eq!(file.try_decrypt(), Err(DecryptError::Integrity));
+}
@@ -0,0 +1,126 @@
+use include_crypt::{
+    codegen::encrypt_xor, crypto::compression::Algorithm, include_crypt, include_dir, EncryptedFile, EncryptedFolder,
+};
+
+#[test]
+fn test_compress_none() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(AES, "tests/test.data", compress = none);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+
+    let file: EncryptedFile = include_crypt!(XOR, "tests/test.data", 0xdeadbeef, stream, compress = none, mac);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+
+    let file: EncryptedFile = include_crypt!(XOR + CHACHA, "tests/test.data", mac, compress = none);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+#[cfg(feature = "deflate")]
+fn test_compress_deflate() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(AES_GCM, "tests/test.data", compress = deflate);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+
+    let file: EncryptedFile = include_crypt!("tests/test.data", compress = deflate(10));
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn test_compress_zstd() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(CHACHA, "tests/test.data", compress = zstd(19));
+    assert_eq!(file.decrypt_str().unwrap(), content);
+
+    let file: EncryptedFile = include_crypt!(
+        AES_GCM,
+        "tests/test.data",
+        master_key_file = "tests/master.key",
+        compress = zstd
+    );
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+fn test_compress_zstd_dictionary() {
+    let content = std::fs::read_to_string("src/lib.rs").unwrap();
+
+    let folder: EncryptedFolder = include_dir!(AES_GCM, "src/", compress = zstd_dict, mac);
+    let file = folder.get("lib.rs").expect("Couldn't find file");
+    assert_eq!(file.decrypt_str().unwrap(), content);
+
+    let folder: EncryptedFolder = include_dir!(
+        CHACHA,
+        "src/",
+        master_key_file = "tests/master.key",
+        compress = zstd_dict(19)
+    );
+    let file = folder.get("lib.rs").expect("Couldn't find file");
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+#[cfg(feature = "lz4")]
+fn test_compress_lz4() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(XOR, "tests/test.data", compress = lz4, mac);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+#[cfg(feature = "brotli")]
+fn test_compress_brotli() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(AES, "tests/test.data", 0xdeadbeef, compress = brotli(5));
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_compress_folder() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let folder: EncryptedFolder = include_dir!(AES_GCM, "tests/", compress = none, mac);
+    let file = folder.get("test.data").expect("Couldn't find file");
+    assert_eq!(file.decrypt_str().unwrap(), content);
+
+    let folder: EncryptedFolder = include_dir!(XOR, "tests/", stream, compress = none);
+    let file = folder.get("test.data").expect("Couldn't find file");
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_compress_incompressible() {
+    // The key is all zeros, so the embedded buffer is the content followed by
+    // the id of the compression algorithm. The sealed file is already encrypted,
+    // so compressing it doesn't pay off and it's stored as it is.
+    //
+    let (_, data) = encrypt_xor!("tests/sealed/test.data", 0x00);
+    assert_eq!(data[data.len() - 1], Algorithm::None.id());
+    assert_eq!(
+        &data[..data.len() - 1],
+        &std::fs::read("tests/sealed/test.data").unwrap()[..]
+    );
+}
+
+#[test]
+#[cfg(feature = "compression")]
+fn test_compress_default() {
+    let (_, data) = encrypt_xor!("src/lib.rs", 0x00);
+    assert_eq!(data[data.len() - 1], Algorithm::Deflate.id());
+    assert!(data.len() < std::fs::read("src/lib.rs").unwrap().len());
+}
+
+#[test]
+#[cfg(not(feature = "compression"))]
+fn test_compress_default() {
+    let (_, data) = encrypt_xor!("src/lib.rs", 0x00);
+    assert_eq!(data[data.len() - 1], Algorithm::None.id());
+    assert_eq!(&data[..data.len() - 1], &std::fs::read("src/lib.rs").unwrap()[..]);
+}
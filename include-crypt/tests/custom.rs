@@ -0,0 +1,49 @@
+use include_crypt::{
+    crypto::{
+        cipher::{Cipher, CipherError},
+        key::EncryptionKey,
+    },
+    include_crypt, include_dir,
+};
+
+/// A simple cipher that adds the key to the data. The files in `tests/sealed`
+/// have been sealed with it.
+struct AddCipher;
+
+impl Cipher for AddCipher {
+    fn key_len(&self) -> usize { 16 }
+
+    fn encrypt(&self, data: &mut [u8], key: &EncryptionKey, _: &EncryptionKey) -> Result<Vec<u8>, CipherError> {
+        data.iter_mut()
+            .zip(key.iter().cycle())
+            .for_each(|(d, k)| *d = d.wrapping_add(*k));
+
+        Ok(Vec::new())
+    }
+
+    fn decrypt(&self, data: &mut [u8], key: &EncryptionKey, _: &EncryptionKey, _: &[u8]) -> Result<(), CipherError> {
+        data.iter_mut()
+            .zip(key.iter().cycle())
+            .for_each(|(d, k)| *d = d.wrapping_sub(*k));
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_custom_cipher() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(AddCipher, "tests/sealed/test.data");
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_folder_custom_cipher() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let folder = include_dir!(AddCipher, "tests/sealed");
+    let file = folder.get("test.data").expect("Couldn't find file");
+
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
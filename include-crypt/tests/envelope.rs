@@ -0,0 +1,82 @@
+use include_crypt::{
+    crypto::{
+        envelope::{rewrap_key, unwrap_key, wrap_key, MASTER_KEY_LEN},
+        key::EncryptionKey,
+    },
+    include_crypt, include_dir, DecryptError, EncryptedFile, EncryptedFolder, Keyring, WrappedKey,
+};
+
+#[test]
+fn test_envelope_embedded_master_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(AES_GCM, "tests/test.data", master_key_file = "tests/master.key");
+    assert_eq!(file.decrypt(), content.as_bytes());
+    assert!(file.key_id().is_none());
+
+    let file: EncryptedFile = include_crypt!(XOR, "tests/test.data", master_key_file = "tests/master.key", mac);
+    assert_eq!(file.decrypt(), content.as_bytes());
+}
+
+#[test]
+fn test_envelope_external_master_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+    let keyring = Keyring::from_file("tests/keyring.toml").unwrap();
+
+    let file: EncryptedFile = include_crypt!(
+        CHACHA,
+        "tests/test.data",
+        master_key_id = "master-2026",
+        keyring = "tests/keyring.toml"
+    );
+    assert_eq!(file.key_id(), Some("master-2026"));
+    assert_eq!(file.try_decrypt(), Err(DecryptError::MissingKey));
+    assert_eq!(file.decrypt_with_keyring(&keyring).unwrap(), content.as_bytes());
+
+    let mut keyring = Keyring::new();
+    keyring.insert("master-2026", EncryptionKey::random(MASTER_KEY_LEN));
+    assert_eq!(file.decrypt_with_keyring(&keyring), Err(DecryptError::Integrity));
+}
+
+#[test]
+fn test_envelope_rewrap_key() {
+    let old_master_key = EncryptionKey::random(MASTER_KEY_LEN);
+    let new_master_key = EncryptionKey::random(MASTER_KEY_LEN);
+    let data_key = EncryptionKey::random(32);
+
+    let wrapped = wrap_key(&data_key, &old_master_key, &EncryptionKey::random(12)).unwrap();
+    let rewrapped = rewrap_key(&wrapped, &old_master_key, &new_master_key, &EncryptionKey::random(12)).unwrap();
+    assert_eq!(*unwrap_key(&rewrapped, &new_master_key).unwrap(), *data_key);
+}
+
+#[test]
+fn test_envelope_folder() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let folder: EncryptedFolder = include_dir!(AES_GCM, "./tests", master_key_file = "tests/master.key");
+    assert!(matches!(folder.wrapped_key(), Some(WrappedKey::Embedded(..))));
+    assert_eq!(folder.get("test.data").unwrap().decrypt(), content.as_bytes());
+
+    let unwrapped = folder.unwrap_key().unwrap();
+    assert_eq!(unwrapped.decrypt("test.data").unwrap().unwrap(), content.as_bytes());
+    assert!(unwrapped.decrypt("missing.data").is_none());
+}
+
+#[test]
+fn test_envelope_folder_external_master_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+    let keyring = Keyring::from_file("tests/keyring.toml").unwrap();
+
+    let folder: EncryptedFolder = include_dir!(
+        XOR,
+        "./tests",
+        stream,
+        master_key_id = "master-2026",
+        keyring = "tests/keyring.toml",
+        mac
+    );
+    assert_eq!(folder.unwrap_key().err(), Some(DecryptError::MissingKey));
+
+    let unwrapped = folder.unwrap_key_with(&keyring).unwrap();
+    assert_eq!(unwrapped.decrypt("test.data").unwrap().unwrap(), content.as_bytes());
+}
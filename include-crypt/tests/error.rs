@@ -0,0 +1,54 @@
+use include_crypt::{
+    crypto::xor::Xor,
+    include_crypt,
+    obfstr::{obfconst, ObfString},
+    DecryptError, EncryptedFile, EncryptionType,
+};
+
+#[test]
+fn test_invalid_key_format() {
+    static KEY: ObfString<[u8; 64]> = obfconst!("zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz");
+
+    let file = EncryptedFile::new(b"Hello World", EncryptionType::Custom(&Xor, &KEY, &[], b""));
+    assert_eq!(file.try_decrypt(), Err(DecryptError::KeyFormat));
+}
+
+#[test]
+fn test_decrypt_aborts() {
+    static KEY: ObfString<[u8; 64]> = obfconst!("zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz");
+
+    // The abort can't be caught, so the test runs itself in a child process.
+    //
+    if std::env::var_os("INCLUDE_CRYPT_ABORT_TEST").is_some() {
+        let file = EncryptedFile::new(b"Hello World", EncryptionType::Custom(&Xor, &KEY, &[], b""));
+        file.decrypt();
+        return;
+    }
+
+    let status = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "test_decrypt_aborts", "--test-threads=1"])
+        .env("INCLUDE_CRYPT_ABORT_TEST", "1")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .unwrap();
+    assert!(!status.success());
+}
+
+#[test]
+fn test_invalid_utf8() {
+    // The sealed file is binary data which isn't valid utf-8.
+    //
+    let file = include_crypt!(XOR, "tests/sealed/test.data");
+    assert!(file.try_decrypt().is_ok());
+    assert!(matches!(file.try_decrypt_str(), Err(DecryptError::Utf8(_))));
+}
+
+#[test]
+fn test_invalid_compression() {
+    let file = EncryptedFile::new(
+        b"\xff\xff\xff\xff",
+        EncryptionType::Xor(include_crypt::SplitKey::new(|key| *key = [0u8; 32])),
+    );
+    assert_eq!(file.try_decrypt(), Err(DecryptError::Decompression));
+}
@@ -10,6 +10,16 @@ fn test_folder_xor_get_file() {
     assert_eq!(file.decrypt_str().unwrap(), content);
 }
 
+#[test]
+fn test_folder_xor_stream_get_file() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let folder = include_dir!(XOR, "tests/", stream);
+    let file = folder.get("test.data").expect("Couldn't find file");
+
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
 #[test]
 fn test_folder_aes_get_file() {
     let content = std::fs::read_to_string("tests/test.data").unwrap();
@@ -19,3 +29,41 @@ fn test_folder_aes_get_file() {
 
     assert_eq!(file.decrypt_str().unwrap(), content);
 }
+
+#[test]
+fn test_folder_aes_gcm_get_file() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let folder = include_dir!(AES_GCM, "tests/");
+    let file = folder.get("test.data").expect("Couldn't find file");
+
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_folder_chacha_get_file() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let folder = include_dir!(CHACHA, "tests/");
+    let file = folder.get("test.data").expect("Couldn't find file");
+
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_folder_derived_keys() {
+    let folder = include_dir!(AES_GCM, "tests/", mac);
+
+    // Every file has its own key and nonce, which are derived from the folder key.
+    //
+    for entry in std::fs::read_dir("tests").unwrap() {
+        let path = entry.unwrap().path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        let file = folder.get(file_name).expect("Couldn't find file");
+        assert_eq!(file.try_decrypt().unwrap(), std::fs::read(&path).unwrap());
+    }
+}
@@ -0,0 +1,39 @@
+use include_crypt::{
+    crypto::key::EncryptionKey, include_crypt, include_dir, DecryptError, DecryptedGuard, EncryptedFile,
+};
+
+fn assert_guard(file: &EncryptedFile) {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let guard = file.decrypt_guard().unwrap();
+    assert_eq!(guard.as_str().unwrap(), content);
+    assert_eq!(file.with_decrypted(|bytes| bytes == content.as_bytes()), Ok(true));
+}
+
+#[test]
+fn test_guard_embedded_key() {
+    assert_guard(&include_crypt!(XOR, "tests/test.data"));
+    assert_guard(&include_crypt!(XOR, "tests/test.data", stream));
+    assert_guard(&include_crypt!(AES, "tests/test.data"));
+    assert_guard(&include_crypt!(AES_GCM, "tests/test.data"));
+    assert_guard(&include_crypt!(CHACHA, "tests/test.data"));
+}
+
+#[test]
+fn test_guard_folder() {
+    let folder = include_dir!(AES, "tests/");
+    assert_guard(folder.get("test.data").expect("Couldn't find file"));
+}
+
+#[test]
+fn test_guard_runtime_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(AES_GCM, "tests/test.data", 0xABCDEF0123456789, key_id = "guard-key");
+    assert_eq!(file.with_decrypted(|_| ()), Err(DecryptError::MissingKey));
+
+    let provider = |_: &str| EncryptionKey::from_hex("0xABCDEF0123456789").ok();
+    let guard = DecryptedGuard::from(file.decrypt_with(&provider).unwrap());
+    assert_eq!(&*guard, content.as_bytes());
+    assert!(!format!("{:?}", guard).contains(&content));
+}
@@ -0,0 +1,53 @@
+use include_crypt::{crypto::key::EncryptionKey, include_crypt, DecryptError, EncryptedFile, Keyring};
+
+#[test]
+fn test_keyring_rotation() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+    let keyring = Keyring::from_file("tests/keyring.toml").unwrap();
+
+    let old: EncryptedFile = include_crypt!(
+        AES_GCM,
+        "tests/test.data",
+        key_id = "2026-q2",
+        keyring = "tests/keyring.toml"
+    );
+    let new: EncryptedFile = include_crypt!(
+        CHACHA,
+        "tests/test.data",
+        key_id = "2026-q3",
+        keyring = "tests/keyring.toml"
+    );
+    assert_eq!(old.key_id(), Some("2026-q2"));
+    assert_eq!(new.key_id(), Some("2026-q3"));
+
+    assert_eq!(old.decrypt_with_keyring(&keyring).unwrap(), content.as_bytes());
+    assert_eq!(new.decrypt_with_keyring(&keyring).unwrap(), content.as_bytes());
+}
+
+#[test]
+fn test_keyring_unknown_key_id() {
+    let file: EncryptedFile = include_crypt!(
+        AES,
+        "tests/test.data",
+        key_id = "2026-q3",
+        keyring = "tests/keyring.toml"
+    );
+
+    let mut keyring = Keyring::new();
+    keyring.insert(
+        "2026-q2",
+        EncryptionKey::from_hex("0x00112233445566778899aabbccddeeff").unwrap(),
+    );
+    assert_eq!(
+        file.decrypt_with_keyring(&keyring),
+        Err(DecryptError::UnknownKeyId("2026-q3"))
+    );
+}
+
+#[test]
+fn test_keyring_embedded_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(XOR, "tests/test.data");
+    assert_eq!(file.decrypt_with_keyring(&Keyring::new()).unwrap(), content.as_bytes());
+}
@@ -0,0 +1,19 @@
+#![cfg(all(feature = "secure-memory", target_os = "linux"))]
+
+use include_crypt::{include_crypt, DecryptError};
+
+#[test]
+fn test_locked() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(AES_GCM, "tests/test.data");
+    let buffer = file.decrypt_locked().unwrap();
+    assert_eq!(&*buffer, content.as_bytes());
+    assert!(!format!("{:?}", buffer).contains(&content));
+}
+
+#[test]
+fn test_locked_missing_key() {
+    let file = include_crypt!(CHACHA, "tests/test.data", 0xABCDEF0123456789, key_id = "locked-key");
+    assert_eq!(file.decrypt_locked().err(), Some(DecryptError::MissingKey));
+}
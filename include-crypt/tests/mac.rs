@@ -0,0 +1,84 @@
+use include_crypt::{
+    codegen::encrypt_xor,
+    crypto::{
+        key::EncryptionKey,
+        mac::{hmac_sha256, MAC_TAG_LEN},
+    },
+    include_crypt, include_dir, DecryptError, EncryptedFile, EncryptedFolder, EncryptionType, SplitKey,
+};
+
+#[test]
+fn test_mac() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(XOR, "tests/test.data", mac);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+
+    let file: EncryptedFile = include_crypt!(XOR, "tests/test.data", 0xdeadbeef, mac);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+
+    let file: EncryptedFile = include_crypt!(XOR, "tests/test.data", stream, mac);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+
+    let file: EncryptedFile = include_crypt!(AES, "tests/test.data", mac);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+
+    let file: EncryptedFile = include_crypt!(CHACHA, "tests/test.data", mac);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+
+    let file: EncryptedFile = include_crypt!("tests/test.data", mac);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_mac_cascade() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(XOR_STREAM + AES, "tests/test.data", mac);
+    assert_eq!(file.decrypt_str().unwrap(), content);
+}
+
+#[test]
+fn test_mac_key_sources() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(AES_GCM, "tests/test.data", 0xABCDEF0123456789, key_id = "test-key", mac);
+    let provider = |_: &str| EncryptionKey::from_hex("0xABCDEF0123456789").ok();
+    assert_eq!(file.decrypt_with(&provider).unwrap(), content.as_bytes());
+
+    let file = include_crypt!(PASSWORD, "tests/test.data", env = "CARGO_PKG_NAME", kdf = "pbkdf2", mac);
+    assert_eq!(file.decrypt_with_password("include-crypt").unwrap(), content.as_bytes());
+
+    let file = include_crypt!(X25519, "tests/test.data", public_key_file = "tests/x25519.pub", mac);
+    let secret_key =
+        EncryptionKey::from_hex("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb").unwrap();
+    assert_eq!(file.decrypt_with_secret_key(&secret_key).unwrap(), content.as_bytes());
+}
+
+#[test]
+fn test_mac_folder() {
+    let folder: EncryptedFolder = include_dir!(AES, "tests/", mac);
+
+    let file = folder.get("test.data").unwrap();
+    assert_eq!(
+        file.decrypt_str().unwrap(),
+        std::fs::read_to_string("tests/test.data").unwrap()
+    );
+}
+
+#[test]
+fn test_mac_tampered() {
+    let (key, data) = encrypt_xor!("tests/test.data", 0xdeadbeef);
+    let mac: &'static [u8; MAC_TAG_LEN] = Box::leak(Box::new(hmac_sha256(data, &[7u8; 32])));
+
+    let file = EncryptedFile::new(data, EncryptionType::Xor(key)).with_mac(SplitKey::new(|key| *key = [7u8; 32]), mac);
+    assert!(file.try_decrypt().is_ok());
+
+    let (key, data) = encrypt_xor!("tests/test.data", 0xdeadbeef);
+    let mut tampered = data.to_vec();
+    tampered[0] ^= 1;
+
+    let file = EncryptedFile::new(Box::leak(tampered.into_boxed_slice()), EncryptionType::Xor(key))
+        .with_mac(SplitKey::new(|key| *key = [7u8; 32]), mac);
+    assert_eq!(file.try_decrypt(), Err(DecryptError::Mac));
+}
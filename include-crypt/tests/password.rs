@@ -0,0 +1,31 @@
+use include_crypt::{include_crypt, DecryptError, EncryptedFile};
+
+#[test]
+fn test_password_argon2() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(PASSWORD, "tests/test.data", file = "tests/password.txt");
+    assert_eq!(
+        file.decrypt_with_password("correct horse battery staple").unwrap(),
+        content.as_bytes()
+    );
+}
+
+#[test]
+fn test_password_pbkdf2() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(PASSWORD, "tests/test.data", env = "CARGO_PKG_NAME", kdf = "pbkdf2");
+    assert_eq!(file.decrypt_with_password("include-crypt").unwrap(), content.as_bytes());
+}
+
+#[test]
+fn test_password_wrong() {
+    let file = include_crypt!(PASSWORD, "tests/test.data", env = "CARGO_PKG_NAME", kdf = "pbkdf2");
+
+    assert_eq!(
+        file.decrypt_with_password("wrong password"),
+        Err(DecryptError::Integrity)
+    );
+    assert_eq!(file.try_decrypt(), Err(DecryptError::MissingKey));
+}
@@ -0,0 +1,53 @@
+use include_crypt::{crypto::key::EncryptionKey, include_crypt, DecryptError, EncryptedFile, KeyProvider};
+
+fn provider(key_id: &str) -> Option<EncryptionKey> {
+    match key_id {
+        "test-key" => EncryptionKey::from_hex("0xABCDEF0123456789").ok(),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_provider_aes_gcm() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(AES_GCM, "tests/test.data", 0xABCDEF0123456789, key_id = "test-key");
+    assert_eq!(file.key_id(), Some("test-key"));
+    assert_eq!(file.decrypt_with(&provider).unwrap(), content.as_bytes());
+}
+
+#[test]
+fn test_provider_xor() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(XOR, "tests/test.data", 0xABCDEF0123456789, key_id = "test-key");
+    assert_eq!(file.decrypt_with(&provider).unwrap(), content.as_bytes());
+}
+
+#[test]
+fn test_provider_missing_key() {
+    let file = include_crypt!(CHACHA, "tests/test.data", 0xABCDEF0123456789, key_id = "other-key");
+
+    assert_eq!(file.decrypt_with(&provider), Err(DecryptError::MissingKey));
+    assert_eq!(file.try_decrypt(), Err(DecryptError::MissingKey));
+}
+
+#[test]
+fn test_provider_wrong_key() {
+    let file = include_crypt!(AES_GCM, "tests/test.data", 0xABCDEF0123456789, key_id = "test-key");
+
+    let wrong_key = |_: &str| EncryptionKey::from_hex("0xdeadbeef").ok();
+    assert_eq!(file.decrypt_with(&wrong_key), Err(DecryptError::Integrity));
+}
+
+#[test]
+fn test_provider_embedded_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(AES, "tests/test.data");
+    assert_eq!(file.key_id(), None);
+    assert_eq!(
+        file.decrypt_with(&provider as &dyn KeyProvider).unwrap(),
+        content.as_bytes()
+    );
+}
@@ -0,0 +1,66 @@
+use include_crypt::{crypto::key::EncryptionKey, include_crypt, DecryptError, EncryptedFile, Keyring};
+
+const CUSTOMER_A: &str = "0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a";
+const CUSTOMER_B: &str = "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b";
+
+#[test]
+fn test_recipients_decrypt_with_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(
+        AES_GCM,
+        "tests/test.data",
+        recipients = ["customer-a", "customer-b"],
+        keyring = "tests/keyring.toml"
+    );
+    assert_eq!(file.try_decrypt(), Err(DecryptError::MissingKey));
+    assert_eq!(
+        file.recipients().unwrap().collect::<Vec<_>>(),
+        vec!["customer-a", "customer-b"]
+    );
+
+    for key in [CUSTOMER_A, CUSTOMER_B] {
+        let key = EncryptionKey::from_hex(key).unwrap();
+        assert_eq!(file.decrypt_with_key(&key).unwrap(), content.as_bytes());
+    }
+    assert_eq!(
+        file.decrypt_with_key(&EncryptionKey::from_hex("0c").unwrap()),
+        Err(DecryptError::Integrity)
+    );
+}
+
+#[test]
+fn test_recipients_decrypt_with() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(
+        XOR,
+        "tests/test.data",
+        recipients = ["customer-a", "customer-b"],
+        keyring = "tests/keyring.toml",
+        mac
+    );
+
+    let mut keyring = Keyring::new();
+    assert_eq!(file.decrypt_with(&keyring), Err(DecryptError::MissingKey));
+
+    keyring.insert("customer-b", EncryptionKey::from_hex(CUSTOMER_B).unwrap());
+    assert_eq!(file.decrypt_with(&keyring).unwrap(), content.as_bytes());
+    assert_eq!(file.decrypt_with_keyring(&keyring).unwrap(), content.as_bytes());
+
+    keyring.insert("customer-b", EncryptionKey::from_hex(CUSTOMER_A).unwrap());
+    assert_eq!(file.decrypt_with(&keyring), Err(DecryptError::Integrity));
+}
+
+#[test]
+fn test_recipients_embedded_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(AES, "tests/test.data");
+    assert!(file.recipients().is_none());
+    assert_eq!(
+        file.decrypt_with_key(&EncryptionKey::from_hex(CUSTOMER_A).unwrap())
+            .unwrap(),
+        content.as_bytes()
+    );
+}
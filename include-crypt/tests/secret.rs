@@ -0,0 +1,22 @@
+use include_crypt::{include_crypt, include_dir};
+
+#[test]
+fn test_decrypt_secret() {
+    let content = std::fs::read("tests/test.data").unwrap();
+
+    let file = include_crypt!(XOR, "tests/test.data");
+    assert_eq!(*file.decrypt_secret().unwrap(), content);
+
+    let file = include_crypt!(AES_GCM, "tests/test.data");
+    assert_eq!(*file.decrypt_secret().unwrap(), content);
+}
+
+#[test]
+fn test_folder_decrypt_secret() {
+    let content = std::fs::read("tests/test.data").unwrap();
+
+    let folder = include_dir!(CHACHA, "tests/");
+    let file = folder.get("test.data").expect("Couldn't find file");
+
+    assert_eq!(*file.decrypt_secret().unwrap(), content);
+}
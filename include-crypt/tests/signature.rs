@@ -0,0 +1,93 @@
+use include_crypt::{include_crypt, include_dir, DecryptError, EncryptedFile, EncryptedFolder, Keyring, PublicKey};
+
+/// The public key of `tests/signing.key` (RFC 8032).
+const PUBLIC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+
+#[test]
+fn test_signed_file() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+    let public_key = PublicKey::from_hex(PUBLIC_KEY).unwrap();
+
+    let file: EncryptedFile = include_crypt!(AES_GCM, "tests/test.data", sign = "tests/signing.key");
+    assert_eq!(file.public_key(), Some(&public_key));
+    assert_eq!(file.verify_and_decrypt(&public_key).unwrap(), content.as_bytes());
+
+    let file = include_crypt!("tests/test.data", sign = "tests/signing.key", 0xdeadbeef);
+    assert_eq!(file.verify_and_decrypt(&public_key).unwrap(), content.as_bytes());
+
+    let file = include_crypt!(XOR, "tests/test.data", sign = "tests/signing.key", stream);
+    assert_eq!(file.verify_and_decrypt(&public_key).unwrap(), content.as_bytes());
+}
+
+#[test]
+fn test_signed_file_runtime_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+    let public_key = PublicKey::from_hex(PUBLIC_KEY).unwrap();
+
+    let file = include_crypt!(
+        PASSWORD,
+        "tests/test.data",
+        sign = "tests/signing.key",
+        env = "CARGO_PKG_NAME",
+        kdf = "pbkdf2"
+    );
+    let data = file.decrypt_with_password("include-crypt").unwrap();
+    assert_eq!(file.verify(&data, &public_key), Ok(()));
+    assert_eq!(data, content.as_bytes());
+}
+
+#[test]
+fn test_signed_file_invalid() {
+    let public_key = PublicKey::from_hex(PUBLIC_KEY).unwrap();
+    let other_key = PublicKey::new([1; 32]);
+
+    let file = include_crypt!(AES, "tests/test.data", sign = "tests/signing.key");
+    assert_eq!(file.verify_and_decrypt(&other_key), Err(DecryptError::Signature));
+    assert_eq!(file.verify(b"Hello World", &public_key), Err(DecryptError::Signature));
+
+    let file = include_crypt!(AES, "tests/test.data");
+    assert_eq!(file.public_key(), None);
+    assert_eq!(file.verify_and_decrypt(&public_key), Err(DecryptError::Signature));
+}
+
+#[test]
+fn test_signed_folder() {
+    let public_key = PublicKey::from_hex(PUBLIC_KEY).unwrap();
+
+    let folder: EncryptedFolder = include_dir!(CHACHA, "./src", sign = "tests/signing.key");
+    assert_eq!(folder.public_key(), Some(&public_key));
+    assert_eq!(folder.verify(&public_key), Ok(()));
+    assert_eq!(folder.verify(&PublicKey::new([1; 32])), Err(DecryptError::Signature));
+
+    let folder = include_dir!("./src", sign = "tests/signing.key");
+    assert_eq!(folder.verify(&public_key), Ok(()));
+
+    let folder = include_dir!(AES, "./src");
+    assert_eq!(folder.verify(&public_key), Err(DecryptError::Signature));
+}
+
+#[test]
+fn test_signed_folder_external_master_key() {
+    let public_key = PublicKey::from_hex(PUBLIC_KEY).unwrap();
+    let keyring = Keyring::from_file("tests/keyring.toml").unwrap();
+
+    let folder: EncryptedFolder = include_dir!(
+        AES_GCM,
+        "./src",
+        sign = "tests/signing.key",
+        master_key_id = "master-2026",
+        keyring = "tests/keyring.toml"
+    );
+    assert_eq!(folder.verify(&public_key), Err(DecryptError::MissingKey));
+    assert_eq!(folder.verify_with(&public_key, &keyring), Ok(()));
+    assert_eq!(
+        folder.verify_with(&PublicKey::new([1; 32]), &keyring),
+        Err(DecryptError::Signature)
+    );
+
+    let folder = EncryptedFolder {
+        files: folder.files,
+        signature: None,
+    };
+    assert_eq!(folder.verify_with(&public_key, &keyring), Err(DecryptError::Signature));
+}
@@ -0,0 +1,140 @@
+use include_crypt::{
+    codegen::encrypt_xor, crypto::key::EncryptionKey, include_crypt, include_dir, DecryptError, EncryptedFile,
+    EncryptedFolder, EncryptionType, Keyring, PublicKey, SolidArchive,
+};
+
+#[test]
+fn test_solid_folder() {
+    let folder: EncryptedFolder = include_dir!(AES, "tests/", solid);
+
+    for entry in std::fs::read_dir("tests").unwrap() {
+        let path = entry.unwrap().path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        let file = folder.get(file_name).expect("Couldn't find file");
+        assert_eq!(file.try_decrypt().unwrap(), std::fs::read(&path).unwrap());
+    }
+    assert!(folder.get("missing.data").is_none());
+
+    let content = std::fs::read("tests/sealed/test.data").unwrap();
+    let file = folder.get("sealed/test.data").expect("Couldn't find file");
+    assert_eq!(file.decrypt(), content);
+}
+
+#[test]
+fn test_solid_folder_options() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let folder: EncryptedFolder = include_dir!(XOR, "tests/", stream, solid, mac);
+    assert_eq!(folder.get("test.data").unwrap().decrypt_str().unwrap(), content);
+
+    let folder: EncryptedFolder = include_dir!(CHACHA, "tests/", compress = none, solid);
+    assert_eq!(folder.get("test.data").unwrap().decrypt_str().unwrap(), content);
+
+    let folder: EncryptedFolder = include_dir!(AES_GCM, "./src", sign = "tests/signing.key", solid);
+    let public_key = folder.public_key().cloned().unwrap();
+    assert_eq!(folder.verify(&public_key), Ok(()));
+    assert_eq!(folder.verify(&PublicKey::new([1; 32])), Err(DecryptError::Signature));
+}
+
+#[test]
+fn test_solid_folder_threads() {
+    static FOLDER: EncryptedFolder = include_dir!(AES_GCM, "tests/", solid);
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    // The archive is decrypted once, no matter which thread accesses it first.
+    //
+    let threads = (0..8)
+        .map(|_| std::thread::spawn(|| FOLDER.get("test.data").unwrap().decrypt_str().unwrap()))
+        .collect::<Vec<_>>();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), content);
+    }
+}
+
+#[test]
+fn test_solid_folder_envelope() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+    let keyring = Keyring::from_file("tests/keyring.toml").unwrap();
+
+    let folder: EncryptedFolder = include_dir!(AES_GCM, "tests/", master_key_file = "tests/master.key", solid);
+    assert!(folder.wrapped_key().is_some());
+    let unwrapped = folder.unwrap_key().unwrap();
+    assert_eq!(unwrapped.decrypt("test.data").unwrap().unwrap(), content.as_bytes());
+
+    let folder: EncryptedFolder = include_dir!(
+        AES,
+        "tests/",
+        master_key_id = "master-2026",
+        keyring = "tests/keyring.toml",
+        solid
+    );
+    let file = folder.get("test.data").unwrap();
+    assert_eq!(file.key_id(), Some("master-2026"));
+    assert_eq!(file.try_decrypt(), Err(DecryptError::MissingKey));
+    assert_eq!(file.decrypt_with(&keyring).unwrap(), content.as_bytes());
+
+    // The decrypted archive is only returned to calls that supply the key.
+    //
+    assert_eq!(file.try_decrypt(), Err(DecryptError::MissingKey));
+    assert_eq!(file.decrypt_slice(), Some(Err(DecryptError::MissingKey)));
+    let unwrapped = folder.unwrap_key_with(&keyring).unwrap();
+    assert_eq!(
+        unwrapped.decrypt_slice("test.data").unwrap().unwrap(),
+        content.as_bytes()
+    );
+}
+
+#[test]
+fn test_solid_folder_retry() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+    let keyring = Keyring::from_file("tests/keyring.toml").unwrap();
+    let wrong_key = |_: &str| EncryptionKey::from_hex("0xdeadbeef").ok();
+
+    let folder: EncryptedFolder = include_dir!(
+        CHACHA,
+        "tests/",
+        master_key_id = "master-2026",
+        keyring = "tests/keyring.toml",
+        solid
+    );
+    let file = folder.get("test.data").unwrap();
+
+    // Failed calls aren't cached, so the archive can still be decrypted with
+    // the right key.
+    //
+    assert_eq!(file.decrypt_with(&wrong_key), Err(DecryptError::Integrity));
+    assert_eq!(file.decrypt_with(&keyring).unwrap(), content.as_bytes());
+    assert_eq!(file.decrypt_with(&wrong_key), Err(DecryptError::Integrity));
+    assert_eq!(file.decrypt_with(&keyring).unwrap(), content.as_bytes());
+}
+
+#[test]
+fn test_solid_folder_slice() {
+    static FOLDER: EncryptedFolder = include_dir!(CHACHA, "tests/", solid);
+    let content = std::fs::read("tests/test.data").unwrap();
+
+    let slice: &'static [u8] = FOLDER.get("test.data").unwrap().decrypt_slice().unwrap().unwrap();
+    assert_eq!(slice, &content[..]);
+
+    let file: EncryptedFile = include_crypt!(CHACHA, "tests/test.data");
+    assert_eq!(file.decrypt_slice(), None);
+}
+
+#[test]
+fn test_solid_folder_invalid_index() {
+    // The archive is a plain file, so its index is invalid.
+    //
+    let (key, data) = encrypt_xor!("tests/test.data", 0x00);
+    let archive: &'static SolidArchive = Box::leak(Box::new(SolidArchive::new(EncryptedFile::new(
+        data,
+        EncryptionType::Xor(key),
+    ))));
+
+    let file = EncryptedFile::new(&[], EncryptionType::Solid(archive, "test.data"));
+    assert_eq!(file.try_decrypt(), Err(DecryptError::Archive));
+    assert_eq!(file.decrypt_slice(), Some(Err(DecryptError::Archive)));
+}
@@ -0,0 +1,35 @@
+use include_crypt::{crypto::key::EncryptionKey, include_crypt, DecryptError, EncryptedFile};
+
+/// The secret key of `tests/x25519.pub` (RFC 7748).
+const SECRET_KEY: &str = "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb";
+
+#[test]
+fn test_x25519_public_key() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file: EncryptedFile = include_crypt!(
+        X25519,
+        "tests/test.data",
+        public_key = "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"
+    );
+    let secret_key = EncryptionKey::from_hex(SECRET_KEY).unwrap();
+    assert_eq!(file.decrypt_with_secret_key(&secret_key).unwrap(), content.as_bytes());
+}
+
+#[test]
+fn test_x25519_public_key_file() {
+    let content = std::fs::read_to_string("tests/test.data").unwrap();
+
+    let file = include_crypt!(X25519, "tests/test.data", public_key_file = "tests/x25519.pub");
+    let secret_key = EncryptionKey::from_hex(SECRET_KEY).unwrap();
+    assert_eq!(file.decrypt_with_secret_key(&secret_key).unwrap(), content.as_bytes());
+}
+
+#[test]
+fn test_x25519_wrong_key() {
+    let file = include_crypt!(X25519, "tests/test.data", public_key_file = "tests/x25519.pub");
+
+    assert_eq!(file.decrypt_with_secret_key(&[1; 32]), Err(DecryptError::Integrity));
+    assert_eq!(file.decrypt_with_secret_key(&[1; 16]), Err(DecryptError::KeyLength));
+    assert_eq!(file.try_decrypt(), Err(DecryptError::MissingKey));
+}